@@ -1,25 +1,61 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
 
 use cgmath::*;
-use ignition::{life::Scene, ComponentPools, Position, Rotation, Transform, Velocity};
+use ignition::life::{colony::Archetypes, Scene};
 use legion::World as LegionWorld;
 use legion::*;
 use specs::prelude::*;
 use specs::World as SpecsWorld;
 use specs_derive::*;
 
+use ignition_components::{ComponentPools, Position, Rotation, Transform, Velocity};
+
+/* Every world gets the same 10000 entities, holding a transform, a position, a rotation and a
+   velocity in the insert benches, and all but the rotation otherwise :
+        - simple_insert : creating the entities and their components
+        - simple_iter : adding the velocity to the position of every entity
+        - add_remove : adding a rotation to every entity, then taking it back
+
+   Ignition runs each of them on both of its backends, the sparse set pools generated by ecs!()
+   and the archetype tables of `Scene<Archetypes>`
+*/
+const ENTITIES: usize = 10000;
+
+mod ignition_components {
+    use cgmath::*;
+    use ignition::prelude::*;
+
+    #[derive(Component, Copy, Clone, Debug)]
+    pub struct Transform(pub Matrix4<f32>);
+
+    #[derive(Component, Copy, Clone, Debug)]
+    pub struct Position(pub Vector3<f32>);
+
+    #[derive(Component, Copy, Clone, Debug)]
+    pub struct Rotation(pub Vector3<f32>);
+
+    #[derive(Component, Copy, Clone, Debug)]
+    pub struct Velocity(pub Vector3<f32>);
+
+    ecs!(components: [Transform, Position, Rotation, Velocity]);
+}
+
+// The transforms and rotations only weigh on the entities, they are never read
+#[allow(dead_code)]
 #[derive(Copy, Clone)]
 struct LTransform(Matrix4<f32>);
 
 #[derive(Copy, Clone)]
 struct LPosition(Vector3<f32>);
 
+#[allow(dead_code)]
 #[derive(Copy, Clone)]
 struct LRotation(Vector3<f32>);
 
 #[derive(Copy, Clone)]
 struct LVelocity(Vector3<f32>);
 
+#[allow(dead_code)]
 #[derive(Copy, Clone, Component)]
 #[storage(VecStorage)]
 struct STransform(Matrix4<f32>);
@@ -28,6 +64,7 @@ struct STransform(Matrix4<f32>);
 #[storage(VecStorage)]
 struct SPosition(Vector3<f32>);
 
+#[allow(dead_code)]
 #[derive(Copy, Clone, Component)]
 #[storage(VecStorage)]
 struct SRotation(Vector3<f32>);
@@ -36,15 +73,17 @@ struct SRotation(Vector3<f32>);
 #[storage(VecStorage)]
 struct SVelocity(Vector3<f32>);
 
+/* Insert */
+
 pub fn legion_simple_insert() {
     let mut world = LegionWorld::default();
 
     world.extend(
         (
-            vec![LTransform(Matrix4::from_scale(1.0)); 10000],
-            vec![LPosition(Vector3::unit_x()); 10000],
-            vec![LRotation(Vector3::unit_x()); 10000],
-            vec![LVelocity(Vector3::unit_x()); 10000],
+            vec![LTransform(Matrix4::from_scale(1.0)); ENTITIES],
+            vec![LPosition(Vector3::unit_x()); ENTITIES],
+            vec![LRotation(Vector3::unit_x()); ENTITIES],
+            vec![LVelocity(Vector3::unit_x()); ENTITIES],
         )
             .into_soa(),
     );
@@ -56,7 +95,7 @@ pub fn specs_simple_insert() {
     world.register::<SPosition>();
     world.register::<SRotation>();
     world.register::<SVelocity>();
-    (0..10000).for_each(|_| {
+    (0..ENTITIES).for_each(|_| {
         world
             .create_entity()
             .with(STransform(Matrix4::<f32>::from_scale(1.0)))
@@ -70,7 +109,19 @@ pub fn specs_simple_insert() {
 pub fn ignition_simple_insert() {
     let mut scene: Scene<ComponentPools> = Scene::new();
 
-    for i in 0..10000 {
+    for _i in 0..ENTITIES {
+        let entity = scene.entity();
+        scene.component(entity, Transform(Matrix4::<f32>::from_scale(1.0)));
+        scene.component(entity, Position(Vector3::unit_x()));
+        scene.component(entity, Rotation(Vector3::unit_x()));
+        scene.component(entity, Velocity(Vector3::unit_x()));
+    }
+}
+
+pub fn ignition_archetypes_simple_insert() {
+    let mut scene: Scene<Archetypes> = Scene::new();
+
+    for _i in 0..ENTITIES {
         let entity = scene.entity();
         scene.component(entity, Transform(Matrix4::<f32>::from_scale(1.0)));
         scene.component(entity, Position(Vector3::unit_x()));
@@ -82,17 +133,223 @@ pub fn ignition_simple_insert() {
 fn bench_simple_insert(c: &mut Criterion) {
     let mut group = c.benchmark_group("simple_insert");
     group.bench_function("legion", |b| {
-        b.iter(move || legion_simple_insert());
+        b.iter(legion_simple_insert);
+    });
+    group.bench_function("specs", |b| {
+        b.iter(specs_simple_insert);
+    });
+    group.bench_function("ignition", |b| {
+        b.iter(ignition_simple_insert);
+    });
+    group.bench_function("ignition_archetypes", |b| {
+        b.iter(ignition_archetypes_simple_insert);
+    });
+}
+
+/* Worlds the other benches start from */
+
+fn legion_world() -> (LegionWorld, Vec<legion::Entity>) {
+    let mut world = LegionWorld::default();
+
+    let entities = world
+        .extend(
+            (
+                vec![LTransform(Matrix4::from_scale(1.0)); ENTITIES],
+                vec![LPosition(Vector3::unit_x()); ENTITIES],
+                vec![LVelocity(Vector3::unit_x()); ENTITIES],
+            )
+                .into_soa(),
+        )
+        .to_vec();
+
+    (world, entities)
+}
+
+fn specs_world() -> (SpecsWorld, Vec<specs::Entity>) {
+    let mut world = SpecsWorld::new();
+    world.register::<STransform>();
+    world.register::<SPosition>();
+    world.register::<SRotation>();
+    world.register::<SVelocity>();
+
+    let entities = (0..ENTITIES)
+        .map(|_| {
+            world
+                .create_entity()
+                .with(STransform(Matrix4::<f32>::from_scale(1.0)))
+                .with(SPosition(Vector3::unit_x()))
+                .with(SVelocity(Vector3::unit_x()))
+                .build()
+        })
+        .collect();
+
+    (world, entities)
+}
+
+fn ignition_scene() -> Scene<ComponentPools> {
+    let mut scene: Scene<ComponentPools> = Scene::new();
+
+    for _i in 0..ENTITIES {
+        let entity = scene.entity();
+        scene.component(entity, Transform(Matrix4::<f32>::from_scale(1.0)));
+        scene.component(entity, Position(Vector3::unit_x()));
+        scene.component(entity, Velocity(Vector3::unit_x()));
+    }
+
+    scene
+}
+
+fn ignition_archetypes_scene() -> Scene<Archetypes> {
+    let mut scene: Scene<Archetypes> = Scene::new();
+
+    for _i in 0..ENTITIES {
+        let entity = scene.entity();
+        scene.component(entity, Transform(Matrix4::<f32>::from_scale(1.0)));
+        scene.component(entity, Position(Vector3::unit_x()));
+        scene.component(entity, Velocity(Vector3::unit_x()));
+    }
+
+    scene
+}
+
+/* Iterate */
+
+pub fn legion_simple_iter(world: &mut LegionWorld) {
+    let mut query = <(&mut LPosition, &LVelocity)>::query();
+
+    query.for_each_mut(world, |(position, velocity)| {
+        position.0 += velocity.0;
+    });
+}
+
+pub fn specs_simple_iter(world: &mut SpecsWorld) {
+    let mut positions = world.write_storage::<SPosition>();
+    let velocities = world.read_storage::<SVelocity>();
+
+    for (position, velocity) in (&mut positions, &velocities).join() {
+        position.0 += velocity.0;
+    }
+}
+
+pub fn ignition_simple_iter(scene: &mut Scene<ComponentPools>) {
+    scene
+        .query_mut::<(Position, Velocity), _>(|_entity, (position, velocity)| {
+            position.0 += velocity.0;
+        })
+        .unwrap();
+}
+
+pub fn ignition_archetypes_simple_iter(scene: &mut Scene<Archetypes>) {
+    scene
+        .query_mut::<(Position, Velocity), _>(|_entity, (position, velocity)| {
+            position.0 += velocity.0;
+        })
+        .unwrap();
+}
+
+fn bench_simple_iter(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simple_iter");
+
+    let (mut world, _entities) = legion_world();
+    group.bench_function("legion", |b| {
+        b.iter(|| legion_simple_iter(&mut world));
     });
+
+    let (mut world, _entities) = specs_world();
     group.bench_function("specs", |b| {
-        b.iter(move || specs_simple_insert());
+        b.iter(|| specs_simple_iter(&mut world));
     });
+
+    let mut scene = ignition_scene();
     group.bench_function("ignition", |b| {
-        b.iter(move || ignition_simple_insert());
+        b.iter(|| ignition_simple_iter(&mut scene));
+    });
+
+    let mut scene = ignition_archetypes_scene();
+    group.bench_function("ignition_archetypes", |b| {
+        b.iter(|| ignition_archetypes_simple_iter(&mut scene));
+    });
+}
+
+/* Structural changes */
+
+pub fn legion_add_remove((world, entities): &mut (LegionWorld, Vec<legion::Entity>)) {
+    for entity in entities.iter() {
+        world
+            .entry(*entity)
+            .unwrap()
+            .add_component(LRotation(Vector3::unit_x()));
+    }
+
+    for entity in entities.iter() {
+        world
+            .entry(*entity)
+            .unwrap()
+            .remove_component::<LRotation>();
+    }
+}
+
+pub fn specs_add_remove((world, entities): &mut (SpecsWorld, Vec<specs::Entity>)) {
+    let mut rotations = world.write_storage::<SRotation>();
+
+    for entity in entities.iter() {
+        rotations
+            .insert(*entity, SRotation(Vector3::unit_x()))
+            .unwrap();
+    }
+
+    for entity in entities.iter() {
+        rotations.remove(*entity);
+    }
+}
+
+pub fn ignition_add_remove(scene: &mut Scene<ComponentPools>) {
+    for entity in 0..ENTITIES {
+        scene.component(entity, Rotation(Vector3::unit_x()));
+    }
+
+    for entity in 0..ENTITIES {
+        scene.take_component::<Rotation>(entity).unwrap();
+    }
+}
+
+pub fn ignition_archetypes_add_remove(scene: &mut Scene<Archetypes>) {
+    for entity in 0..ENTITIES {
+        scene.component(entity, Rotation(Vector3::unit_x()));
+    }
+
+    for entity in 0..ENTITIES {
+        scene.take_component::<Rotation>(entity).unwrap();
+    }
+}
+
+// Each run starts from a fresh world, so that none of them reuses what the previous one left
+fn bench_add_remove(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add_remove");
+    group.bench_function("legion", |b| {
+        b.iter_batched_ref(legion_world, legion_add_remove, BatchSize::LargeInput);
+    });
+    group.bench_function("specs", |b| {
+        b.iter_batched_ref(specs_world, specs_add_remove, BatchSize::LargeInput);
+    });
+    group.bench_function("ignition", |b| {
+        b.iter_batched_ref(ignition_scene, ignition_add_remove, BatchSize::LargeInput);
+    });
+    group.bench_function("ignition_archetypes", |b| {
+        b.iter_batched_ref(
+            ignition_archetypes_scene,
+            ignition_archetypes_add_remove,
+            BatchSize::LargeInput,
+        );
     });
 }
 
-criterion_group!(benches, bench_simple_insert);
+criterion_group!(
+    benches,
+    bench_simple_insert,
+    bench_simple_iter,
+    bench_add_remove
+);
 criterion_main!(benches);
 
 /*
@@ -0,0 +1,24 @@
+use crate::{
+    liberty::RuntimeConfiguration,
+    life::{ComponentPoolsTrait, Scene},
+};
+
+/* Implemented by every engine!(), so that helpers can be written once for all of them. Not called
+   `Engine`, the struct engine!() generates is by default :
+        fn spawn_player<E: IgnitionEngine>(engine: &mut E) { ... }
+
+   Helpers which need a window ask for `E: IgnitionEngine<Renderer = Screen>` instead.
+*/
+pub trait IgnitionEngine {
+    type Pools: ComponentPoolsTrait;
+    type Renderer;
+
+    fn scene(&self) -> &Scene<Self::Pools>;
+    fn scene_mut(&mut self) -> &mut Scene<Self::Pools>;
+
+    fn renderer(&self) -> &Self::Renderer;
+    fn renderer_mut(&mut self) -> &mut Self::Renderer;
+
+    fn config(&self) -> &RuntimeConfiguration;
+    fn config_mut(&mut self) -> &mut RuntimeConfiguration;
+}
@@ -13,15 +13,27 @@ macro_rules! unwrap {
 extern crate component;
 extern crate engine;
 
+// Lets the derives refer to `::ignition` from inside the crate too
+extern crate self as ignition;
+
+pub mod cockpit;
 pub mod liberty;
 pub mod life;
 pub mod manifestation;
 
 pub mod prelude {
-    pub use component::Component;
+    pub use component::{Bundle, Component};
+    pub use engine::{ecs, engine, export_components};
     pub use wgpu::include_wgsl;
 
-    //pub use crate::{life::Component, Engine};
+    pub use crate::{
+        cockpit::IgnitionEngine,
+        manifestation::{
+            artist::{commands::Commands, pipeline::Pipeline, Artist},
+            nostalgia::Nostalgia,
+            race_track::RaceTrack,
+        },
+    };
 }
 
 /*
@@ -47,18 +59,13 @@ pub struct Rotation(pub Vector3<f32>);
 [derive(Component, Copy, Clone)]
 pub struct Velocity(pub Vector3<f32>);
 
-engine!();
+engine!(components: [Int]);
 */
 
-use crate::{
-    liberty::RuntimeConfiguration,
-    life::{annihilation::EntityDestructor, Component, ComponentPool, ComponentPoolsTrait, Scene},
-    manifestation::Screen,
-};
 use component::Component;
 use engine::engine;
 
 #[derive(Component, Debug)]
 pub struct Int(pub i32);
 
-engine!();
+engine!(components: [Int]);
@@ -1,14 +1,24 @@
+use std::{any::TypeId, collections::HashMap};
+
 use annihilation::EntityDestructor;
+use census::PoolVisitor;
 use genesis::entity::EntityConstructor;
 use ghost::ComponentToggler;
 use gizmos::PoolToolbox;
+use glitch::{ErrorPolicy, LifeError};
 
 pub mod abduction;
 pub mod annihilation;
+pub mod census;
+pub mod colony;
+pub mod flock;
 pub mod genesis;
 pub mod ghost;
 pub mod gizmos;
 pub mod glitch;
+pub mod hydra;
+pub mod incarnation;
+pub mod vitals;
 
 use component::Component;
 /*
@@ -20,7 +30,14 @@ pub struct Number {
 
 pub struct Scene<P> {
     pub available_entities: Vec<usize>,
+    pub alive: Vec<bool>,
     pub component_pools: P,
+
+    // Pools each disabled entity got disabled in, to only enable it back in those
+    pub disabled_pools: HashMap<usize, Vec<TypeId>>,
+
+    pub error_policy: ErrorPolicy,
+    pub errors: Vec<LifeError>,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -39,7 +56,16 @@ pub trait ComponentPoolTrait:
     fn as_any_mut(&mut self) -> &mut dyn std::any::Any;
 }
 
-pub trait Component<G> {
+/// Implemented by #[derive(Component)], which finds the pool through the accessors engine!() and
+/// ecs!() generate.
+///
+/// # Safety
+///
+/// `Scene::get_many_mut()` borrows several pools again from where they lie, so both methods must :
+/// - return the same pool, borrowed from `component_pools` and lying within it (not behind a
+///   pointer it holds), so that it lives exactly as long as they do
+/// - return a pool no other component of `G` returns
+pub unsafe trait Component<G> {
     fn get_from(component_pools: &G) -> &ComponentPool<Self>
     where
         Self: Sized;
@@ -51,5 +77,23 @@ pub trait Component<G> {
 
 pub trait ComponentPoolsTrait {
     fn new() -> Self;
-    fn delete_entity(&mut self, entity: usize);
+    fn delete_entity(&mut self, entity: usize) -> Result<(), LifeError>;
+    fn disable_entity(
+        &mut self,
+        entity: usize,
+        disabled: &mut Vec<TypeId>,
+    ) -> Result<(), LifeError>;
+    fn enable_entity(&mut self, entity: usize, disabled: &[TypeId]) -> Result<(), LifeError>;
+    fn visit_pools<V: PoolVisitor>(&self, visitor: &mut V);
+}
+
+// What a scene can be built on, the generated pools or `colony::Archetypes`
+pub trait Storage {
+    fn empty() -> Self;
+}
+
+impl<P: ComponentPoolsTrait> Storage for P {
+    fn empty() -> Self {
+        P::new()
+    }
 }
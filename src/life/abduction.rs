@@ -1,7 +1,8 @@
 use std::any::type_name;
 
 use crate::life::{
-    gizmos::PoolToolbox, glitch::LifeError, Component, ComponentPool, ComponentPoolTrait, Scene,
+    gizmos::PoolToolbox, glitch::LifeError, Component, ComponentPool, ComponentPoolTrait,
+    ComponentPoolsTrait, Scene,
 };
 
 impl<P> Scene<P> {
@@ -13,6 +14,18 @@ impl<P> Scene<P> {
         G::get_mut_from(&mut self.component_pools)
     }
 
+    // The entity the next call to `entity()` hands out, a recycled one if any
+    pub fn get_current_entity(&self) -> usize {
+        match self.available_entities[1..].last() {
+            Some(&entity) => entity,
+            None => self.available_entities[0],
+        }
+    }
+}
+
+// `Scene<Archetypes>` has its own, which can't overlap with these as long as `Archetypes` isn't
+// made of pools
+impl<P: ComponentPoolsTrait> Scene<P> {
     pub fn get_component<G: 'static + Component<P>>(&self, entity: usize) -> Result<&G, LifeError> {
         self.get::<G>().get(entity)
     }
@@ -30,10 +43,6 @@ impl<P> Scene<P> {
     ) -> Result<G, LifeError> {
         self.get_mut::<G>().take_entity(entity)
     }
-
-    pub fn get_current_entity(&self) -> usize {
-        self.available_entities[self.available_entities.len() - 1]
-    }
 }
 
 impl<G: 'static> ComponentPool<G> {
@@ -69,7 +78,7 @@ impl<G: 'static> ComponentPoolTrait for ComponentPool<G> {
         self as &mut dyn std::any::Any
     }
 }
-/*
+
 #[cfg(test)]
 mod tests {
     use crate::{life::Scene, ComponentPools};
@@ -93,4 +102,3 @@ mod tests {
         assert_eq!(0, scene.get_current_entity());
     }
 }
-*/
@@ -4,37 +4,73 @@ use crate::life::{
 
 impl<P: ComponentPoolsTrait> Scene<P> {
     pub fn delete(&mut self, entity: usize) {
+        let result = self.try_delete(entity);
+        self.handle(result);
+    }
+
+    // Recycling an entity twice would hand it out to two different owners
+    pub fn try_delete(&mut self, entity: usize) -> Result<(), LifeError> {
+        if !self.is_alive(entity) {
+            return Err(LifeError::EntityNotAlive(entity));
+        }
+
+        self.alive[entity] = false;
         self.available_entities.push(entity);
-        self.delete_entity_from_each_component_pool(entity);
+        self.disabled_pools.remove(&entity);
+        self.delete_entity_from_each_component_pool(entity)
     }
 
-    pub fn delete_entity_from_each_component_pool(&mut self, entity: usize) {
-        self.component_pools.delete_entity(entity);
+    pub fn delete_entity_from_each_component_pool(
+        &mut self,
+        entity: usize,
+    ) -> Result<(), LifeError> {
+        self.component_pools.delete_entity(entity)
     }
 }
 
 impl<G: 'static> ComponentPool<G> {
     pub fn take_entity(&mut self, entity: usize) -> Result<G, LifeError> {
-        let component = self.component_id(entity)?;
-        let last_index = self.packed_array.last().unwrap().clone();
+        let mut component = self.component_id(entity)?;
+
+        // Disabled components live after num_components, so an enabled one is first moved to
+        // the end of the enabled block to keep both blocks contiguous after the swap_remove
+        if component < self.num_components {
+            let last_enabled = self.num_components - 1;
+            self.try_swap_components(component, last_enabled)?;
+
+            component = last_enabled;
+            self.num_components -= 1;
+        }
 
-        self.num_components -= 1;
+        let last_index = self.entity_id(self.packed_array.len() - 1)?;
 
         self.sparse_array[last_index] = component as i32;
         self.sparse_array[entity] = -1;
 
         self.packed_array.swap_remove(component);
-        Ok(self.component_array.swap_remove(component))
+        let taken = self.component_array.swap_remove(component);
+
+        self.check_invariants();
+        Ok(taken)
     }
 }
 
 pub trait EntityDestructor {
     fn delete_entity(&mut self, entity: usize);
+    fn try_delete_entity(&mut self, entity: usize) -> Result<(), LifeError>;
 }
 
 impl<G: 'static> EntityDestructor for ComponentPool<G> {
     fn delete_entity(&mut self, entity: usize) {
-        unwrap!(self.take_entity(entity));
+        unwrap!(self.try_delete_entity(entity));
+    }
+
+    fn try_delete_entity(&mut self, entity: usize) -> Result<(), LifeError> {
+        if !self.has_component(entity) {
+            return Ok(());
+        }
+
+        self.take_entity(entity).map(|_| ())
     }
 }
 
@@ -45,8 +81,8 @@ mod tests {
     #[test]
     fn deleting_an_entity_updates_component_pool_correctly() {
         let mut pool = ComponentPool::empty();
-        pool.assign_component(1, 32 as i32);
-        pool.assign_component(2, 21 as i32);
+        pool.assign_component(1, 32i32);
+        pool.assign_component(2, 21i32);
 
         pool.delete_entity(1);
 
@@ -65,7 +101,7 @@ mod tests {
     #[test]
     fn deleting_last_entity_doesn_t_swap_with_non_existing_component() {
         let mut pool = ComponentPool::empty();
-        pool.assign_component(1, 32 as i32);
+        pool.assign_component(1, 32i32);
 
         pool.delete_entity(1);
 
@@ -84,7 +120,7 @@ mod tests {
     #[test]
     fn deleting_a_non_existing_entity_does_nothing() {
         let mut pool = ComponentPool::empty();
-        pool.assign_component(1, 32 as i32);
+        pool.assign_component(1, 32i32);
         pool.delete_entity(0);
 
         assert_eq!(
@@ -102,8 +138,8 @@ mod tests {
     #[test]
     fn deleting_two_entities_works_as_expected() {
         let mut pool = ComponentPool::empty();
-        pool.assign_component(1, 32 as i32);
-        pool.assign_component(3, 81 as i32);
+        pool.assign_component(1, 32i32);
+        pool.assign_component(3, 81i32);
 
         pool.delete_entity(1);
         pool.delete_entity(3);
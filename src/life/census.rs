@@ -0,0 +1,292 @@
+use std::{collections::BTreeMap, fmt, fmt::Debug};
+
+use crate::life::{ComponentPool, ComponentPoolsTrait, Scene};
+
+impl<P> Scene<P> {
+    pub fn is_alive(&self, entity: usize) -> bool {
+        self.alive.get(entity).cloned().unwrap_or(false)
+    }
+
+    // Every entity ever created, minus the ones waiting to be recycled
+    pub fn len(&self) -> usize {
+        self.available_entities[0] - (self.available_entities.len() - 1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Live entities, by increasing id
+    pub fn entities(&self) -> impl Iterator<Item = usize> + '_ {
+        self.alive
+            .iter()
+            .enumerate()
+            .filter(|(_entity, alive)| **alive)
+            .map(|(entity, _alive)| entity)
+    }
+}
+
+impl<P: ComponentPoolsTrait> Scene<P> {
+    pub fn dump(&self) -> SceneDump {
+        let mut visitor = DumpVisitor::default();
+        self.component_pools.visit_pools(&mut visitor);
+
+        let entities = self
+            .entities()
+            .map(|entity| EntityDump {
+                entity,
+                components: visitor.components.remove(&entity).unwrap_or_default(),
+            })
+            .collect();
+
+        SceneDump { entities }
+    }
+}
+
+pub trait PoolVisitor {
+    fn visit<G: 'static + Debug>(&mut self, name: &'static str, pool: &ComponentPool<G>);
+}
+
+#[derive(Default)]
+struct DumpVisitor {
+    components: BTreeMap<usize, Vec<ComponentDump>>,
+}
+
+impl PoolVisitor for DumpVisitor {
+    fn visit<G: 'static + Debug>(&mut self, name: &'static str, pool: &ComponentPool<G>) {
+        for (id, (entity, component)) in pool
+            .packed_array
+            .iter()
+            .zip(pool.component_array.iter())
+            .enumerate()
+        {
+            self.components
+                .entry(*entity)
+                .or_default()
+                .push(ComponentDump {
+                    name,
+                    enabled: id < pool.num_components,
+                    value: format!("{:?}", component),
+                });
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SceneDump {
+    pub entities: Vec<EntityDump>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityDump {
+    pub entity: usize,
+    pub components: Vec<ComponentDump>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentDump {
+    pub name: &'static str,
+    pub enabled: bool,
+    pub value: String,
+}
+
+impl SceneDump {
+    pub fn to_json(&self) -> String {
+        let entities = self
+            .entities
+            .iter()
+            .map(|entity| entity.to_json())
+            .collect::<Vec<String>>()
+            .join(",");
+
+        format!("{{\"entities\":[{}]}}", entities)
+    }
+}
+
+impl EntityDump {
+    pub fn to_json(&self) -> String {
+        let components = self
+            .components
+            .iter()
+            .map(|component| component.to_json())
+            .collect::<Vec<String>>()
+            .join(",");
+
+        format!(
+            "{{\"entity\":{},\"components\":[{}]}}",
+            self.entity, components
+        )
+    }
+}
+
+impl ComponentDump {
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"name\":{},\"enabled\":{},\"value\":{}}}",
+            escape_json(self.name),
+            self.enabled,
+            escape_json(&self.value)
+        )
+    }
+}
+
+impl fmt::Display for SceneDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Scene ({} entities)", self.entities.len())?;
+
+        for entity in self.entities.iter() {
+            write!(f, "{}", entity)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for EntityDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "  Entity {}", self.entity)?;
+
+        for component in self.components.iter() {
+            writeln!(f, "    {}", component)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ComponentDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self.enabled {
+            true => "enabled",
+            false => "disabled",
+        };
+
+        write!(f, "{} [{}] {}", self.name, state, self.value)
+    }
+}
+
+fn escape_json(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len() + 2);
+    escaped.push('"');
+
+    for character in string.chars() {
+        match character {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        life::{
+            glitch::{ErrorPolicy, LifeError},
+            Scene,
+        },
+        ComponentPools, Int,
+    };
+
+    #[test]
+    fn dump_lists_live_entities_with_their_components() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        let first = scene.entity();
+        let second = scene.entity();
+        let third = scene.entity();
+
+        scene.component(first, Int(4));
+        scene.component(third, Int(-2));
+        scene.delete(second);
+
+        assert_eq!(
+            scene.dump().to_string(),
+            "Scene (2 entities)\n  Entity 0\n    Int [enabled] Int(4)\n  Entity 2\n    Int [enabled] Int(-2)\n"
+        );
+    }
+
+    #[test]
+    fn only_live_entities_are_counted() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+        assert!(scene.is_empty());
+
+        let first = scene.entity();
+        let second = scene.entity();
+        let third = scene.entity();
+        scene.delete(second);
+
+        assert!(scene.is_alive(first) && !scene.is_alive(second) && scene.is_alive(third));
+        assert!(!scene.is_alive(42));
+        assert_eq!(scene.len(), 2);
+        assert_eq!(scene.entities().collect::<Vec<usize>>(), vec![first, third]);
+
+        assert_eq!(scene.entity(), second);
+        assert_eq!(scene.len(), 3);
+    }
+
+    #[test]
+    fn deleting_an_entity_twice_fails() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        let entity = scene.entity();
+        scene.try_delete(entity).unwrap();
+
+        assert_eq!(
+            scene.try_delete(entity),
+            Err(LifeError::EntityNotAlive(entity))
+        );
+        assert_eq!(scene.try_delete(7), Err(LifeError::EntityNotAlive(7)));
+
+        assert_eq!(scene.entity(), entity);
+        assert_eq!(scene.entity(), entity + 1);
+    }
+
+    #[test]
+    fn components_can_only_be_attached_to_live_entities() {
+        let mut scene: Scene<ComponentPools> = Scene::with_error_policy(ErrorPolicy::Collect);
+
+        let entity = scene.entity();
+        scene.delete(entity);
+
+        assert_eq!(
+            scene.try_component(entity, Int(1)),
+            Err(LifeError::EntityNotAlive(entity))
+        );
+        assert_eq!(
+            scene.try_component(7, Int(1)),
+            Err(LifeError::EntityNotAlive(7))
+        );
+
+        scene.component(7, Int(1));
+        assert_eq!(scene.take_errors(), vec![LifeError::EntityNotAlive(7)]);
+
+        // Nothing is handed over with the recycled id
+        assert_eq!(scene.entity(), entity);
+        assert!(scene.get_component::<Int>(entity).is_err());
+        assert!(scene.validate().is_valid());
+    }
+
+    #[test]
+    fn dump_to_json_escapes_component_values() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        let entity = scene.entity();
+        scene.component(entity, Int(7));
+        scene.disable::<Int>(entity);
+
+        assert_eq!(
+            scene.dump().to_json(),
+            r#"{"entities":[{"entity":0,"components":[{"name":"Int","enabled":false,"value":"Int(7)"}]}]}"#
+        );
+
+        assert_eq!(super::escape_json("a\"b\\\n"), r#""a\"b\\\n""#);
+    }
+}
@@ -0,0 +1,540 @@
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+};
+
+use crate::life::{glitch::LifeError, Scene, Storage};
+
+/* Archetype tables, the other way of storing components, picked through the type of the scene :
+        let mut scene: Scene<Archetypes> = Scene::new();
+
+   Entities holding the exact same set of components live in the same table, with a column per
+   component and a row per entity. Going over several components at once walks down columns
+   side by side, but adding or taking a component moves the whole row to another table. Sparse
+   sets make the opposite trade, which `benches/triangle.rs` measures.
+
+   Any 'static type can be stored, there is no list of components to generate pools from.
+   Creating and deleting entities, adding, getting and taking components and querying them are
+   the same calls on both. Everything else is only implemented for the pools :
+        - `EntityRef` and `EntityMut` views (incarnation.rs)
+        - bundles and #[derive(Bundle)] (flock.rs)
+        - enabling and disabling components or whole entities (ghost.rs)
+        - `dump()` and `validate()` (census.rs, vitals.rs)
+        - borrowing pools themselves, `get()`, `get_mut()` and `get_many_mut()` (abduction.rs,
+          hydra.rs)
+*/
+#[derive(Default)]
+pub struct Archetypes {
+    tables: Vec<Table>,
+    // Table and row of every entity holding at least one component
+    locations: Vec<Option<(usize, usize)>>,
+
+    index: HashMap<Vec<TypeId>, usize>,
+}
+
+pub struct Table {
+    // Sorted, and in the same order as the columns
+    pub types: Vec<TypeId>,
+    pub entities: Vec<usize>,
+
+    columns: Vec<Box<dyn Column>>,
+}
+
+impl Scene<Archetypes> {
+    pub fn component<G: 'static>(&mut self, entity: usize, component: G) {
+        let result = self.try_component(entity, component);
+        self.handle(result);
+    }
+
+    pub fn try_component<G: 'static>(
+        &mut self,
+        entity: usize,
+        component: G,
+    ) -> Result<(), LifeError> {
+        if !self.is_alive(entity) {
+            return Err(LifeError::EntityNotAlive(entity));
+        }
+
+        self.component_pools.insert(entity, component);
+        Ok(())
+    }
+
+    pub fn get_component<G: 'static>(&self, entity: usize) -> Result<&G, LifeError> {
+        self.component_pools.get(entity)
+    }
+
+    pub fn get_component_mut<G: 'static>(&mut self, entity: usize) -> Result<&mut G, LifeError> {
+        self.component_pools.get_mut(entity)
+    }
+
+    pub fn take_component<G: 'static>(&mut self, entity: usize) -> Result<G, LifeError> {
+        self.component_pools.take(entity)
+    }
+
+    pub fn delete(&mut self, entity: usize) {
+        let result = self.try_delete(entity);
+        self.handle(result);
+    }
+
+    pub fn try_delete(&mut self, entity: usize) -> Result<(), LifeError> {
+        if !self.is_alive(entity) {
+            return Err(LifeError::EntityNotAlive(entity));
+        }
+
+        self.alive[entity] = false;
+        self.available_entities.push(entity);
+        self.component_pools.relocate(entity, None, None);
+
+        Ok(())
+    }
+
+    // Every entity holding all of the components, table after table :
+    //      scene.query::<(Position, Velocity), _>(|entity, (position, velocity)| ...);
+    pub fn query<Q: ColumnQuery, F: for<'r> FnMut(usize, Q::Refs<'r>)>(&self, mut f: F) {
+        let types = Q::types();
+
+        for table in self.component_pools.tables.iter() {
+            if table.holds(&types) {
+                Q::for_each_in(table, &mut f);
+            }
+        }
+    }
+
+    pub fn query_mut<Q: ColumnQuery, F: for<'r> FnMut(usize, Q::RefsMut<'r>)>(
+        &mut self,
+        mut f: F,
+    ) -> Result<(), LifeError> {
+        let types = Q::types();
+
+        for (i, (id, name)) in types.iter().enumerate() {
+            if types[..i]
+                .iter()
+                .any(|(other_id, _other_name)| other_id == id)
+            {
+                return Err(LifeError::ComponentBorrowedTwice(name));
+            }
+        }
+
+        for table in self.component_pools.tables.iter_mut() {
+            if table.holds(&types) {
+                Q::for_each_mut_in(table, &mut f);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Storage for Archetypes {
+    fn empty() -> Self {
+        Self::default()
+    }
+}
+
+impl Archetypes {
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    pub fn location(&self, entity: usize) -> Option<(usize, usize)> {
+        self.locations.get(entity).cloned().flatten()
+    }
+
+    pub fn insert<G: 'static>(&mut self, entity: usize, component: G) {
+        let id = TypeId::of::<G>();
+        let location = self.location(entity);
+
+        if let Some((table, row)) = location {
+            if let Some(column) = self.tables[table].column_mut::<G>() {
+                column[row] = component;
+                return;
+            }
+        }
+
+        let mut types = match location {
+            Some((table, _row)) => self.tables[table].types.clone(),
+            None => Vec::new(),
+        };
+        let position = types.binary_search(&id).unwrap_err();
+        types.insert(position, id);
+
+        let target = match self.index.get(&types) {
+            Some(&target) => target,
+            None => {
+                let mut columns = match location {
+                    Some((table, _row)) => self.tables[table].empty_columns(),
+                    None => Vec::new(),
+                };
+                columns.insert(position, Box::new(Vec::<G>::new()));
+
+                self.create_table(types, columns)
+            }
+        };
+
+        self.relocate(entity, Some(target), None);
+        self.tables[target]
+            .column_mut::<G>()
+            .unwrap()
+            .push(component);
+    }
+
+    pub fn get<G: 'static>(&self, entity: usize) -> Result<&G, LifeError> {
+        self.location(entity)
+            .and_then(|(table, row)| self.tables[table].column::<G>().map(|x| &x[row]))
+            .ok_or(LifeError::EntityNotBoundToComponent(
+                type_name::<G>(),
+                entity,
+            ))
+    }
+
+    pub fn get_mut<G: 'static>(&mut self, entity: usize) -> Result<&mut G, LifeError> {
+        match self.location(entity) {
+            Some((table, row)) => self.tables[table].column_mut::<G>().map(|x| &mut x[row]),
+            None => None,
+        }
+        .ok_or(LifeError::EntityNotBoundToComponent(
+            type_name::<G>(),
+            entity,
+        ))
+    }
+
+    pub fn take<G: 'static>(&mut self, entity: usize) -> Result<G, LifeError> {
+        let id = TypeId::of::<G>();
+        let not_bound = || LifeError::EntityNotBoundToComponent(type_name::<G>(), entity);
+
+        let (table, row) = self.location(entity).ok_or_else(not_bound)?;
+        let component = match self.tables[table].column_mut::<G>() {
+            Some(column) => column.swap_remove(row),
+            None => return Err(not_bound()),
+        };
+
+        let types = self.tables[table]
+            .types
+            .iter()
+            .filter(|x| **x != id)
+            .cloned()
+            .collect::<Vec<TypeId>>();
+
+        // An entity without any component isn't in any table
+        let target = match (types.is_empty(), self.index.get(&types)) {
+            (true, _) => None,
+            (false, Some(&target)) => Some(target),
+            (false, None) => {
+                let mut columns = self.tables[table].empty_columns();
+                columns.retain(|x| x.element_type() != id);
+
+                Some(self.create_table(types, columns))
+            }
+        };
+
+        self.relocate(entity, target, Some(id));
+        Ok(component)
+    }
+
+    fn create_table(&mut self, types: Vec<TypeId>, columns: Vec<Box<dyn Column>>) -> usize {
+        self.tables.push(Table {
+            types: types.clone(),
+            entities: Vec::new(),
+
+            columns,
+        });
+
+        self.index.insert(types, self.tables.len() - 1);
+        self.tables.len() - 1
+    }
+
+    // Moves the row of `entity` to the end of `target`, dropping the components `target` has no
+    // column for. The column of `taken` has already been dealt with by the caller
+    fn relocate(&mut self, entity: usize, target: Option<usize>, taken: Option<TypeId>) {
+        if let Some((source, row)) = self.location(entity) {
+            match target {
+                Some(target) => {
+                    let (from, to) = pair_mut(&mut self.tables, source, target);
+                    from.move_row(row, Some(to), taken);
+                }
+                None => self.tables[source].move_row(row, None, taken),
+            }
+
+            // The last row took the place of the one that left
+            if let Some(&moved) = self.tables[source].entities.get(row) {
+                self.locations[moved] = Some((source, row));
+            }
+        }
+
+        let location = target.map(|target| {
+            let table = &mut self.tables[target];
+            table.entities.push(entity);
+
+            (target, table.entities.len() - 1)
+        });
+
+        if self.locations.len() <= entity {
+            self.locations.resize(entity + 1, None);
+        }
+
+        self.locations[entity] = location;
+    }
+}
+
+impl Table {
+    pub fn holds(&self, types: &[(TypeId, &'static str)]) -> bool {
+        types
+            .iter()
+            .all(|(id, _name)| self.types.binary_search(id).is_ok())
+    }
+
+    pub fn column<G: 'static>(&self) -> Option<&Vec<G>> {
+        let id = self.types.binary_search(&TypeId::of::<G>()).ok()?;
+        self.columns[id].as_any().downcast_ref::<Vec<G>>()
+    }
+
+    pub fn column_mut<G: 'static>(&mut self) -> Option<&mut Vec<G>> {
+        let id = self.types.binary_search(&TypeId::of::<G>()).ok()?;
+        self.columns[id].as_any_mut().downcast_mut::<Vec<G>>()
+    }
+
+    fn empty_columns(&self) -> Vec<Box<dyn Column>> {
+        self.columns.iter().map(|x| x.empty()).collect()
+    }
+
+    fn move_row(&mut self, row: usize, mut to: Option<&mut Table>, taken: Option<TypeId>) {
+        self.entities.swap_remove(row);
+
+        for (id, column) in self.types.iter().zip(self.columns.iter_mut()) {
+            if Some(*id) == taken {
+                continue;
+            }
+
+            let destination = to
+                .as_mut()
+                .and_then(|to| to.types.binary_search(id).ok().map(|x| &mut to.columns[x]));
+
+            match destination {
+                Some(destination) => column.move_to(row, destination.as_mut()),
+                None => column.remove(row),
+            }
+        }
+    }
+}
+
+fn pair_mut(tables: &mut [Table], a: usize, b: usize) -> (&mut Table, &mut Table) {
+    if a < b {
+        let (left, right) = tables.split_at_mut(b);
+        (&mut left[a], &mut right[0])
+    } else {
+        let (left, right) = tables.split_at_mut(a);
+        (&mut right[0], &mut left[b])
+    }
+}
+
+// A column of components whose type is only known when it's created
+trait Column {
+    fn element_type(&self) -> TypeId;
+    fn empty(&self) -> Box<dyn Column>;
+    fn remove(&mut self, row: usize);
+    fn move_to(&mut self, row: usize, other: &mut dyn Column);
+
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<G: 'static> Column for Vec<G> {
+    fn element_type(&self) -> TypeId {
+        TypeId::of::<G>()
+    }
+
+    fn empty(&self) -> Box<dyn Column> {
+        Box::new(Vec::<G>::new())
+    }
+
+    fn remove(&mut self, row: usize) {
+        self.swap_remove(row);
+    }
+
+    fn move_to(&mut self, row: usize, other: &mut dyn Column) {
+        let component = self.swap_remove(row);
+
+        // Columns are only ever paired by their TypeId
+        other
+            .as_any_mut()
+            .downcast_mut::<Vec<G>>()
+            .unwrap()
+            .push(component);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self as &dyn Any
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self as &mut dyn Any
+    }
+}
+
+pub trait ColumnQuery {
+    type Refs<'r>;
+    type RefsMut<'r>;
+
+    fn types() -> Vec<(TypeId, &'static str)>;
+    fn for_each_in<V: for<'r> FnMut(usize, Self::Refs<'r>)>(table: &Table, visit: &mut V);
+    fn for_each_mut_in<V: for<'r> FnMut(usize, Self::RefsMut<'r>)>(
+        table: &mut Table,
+        visit: &mut V,
+    );
+}
+
+macro_rules! column_query {
+    ($($G:ident $column:ident),+) => {
+        impl<$($G: 'static),+> ColumnQuery for ($($G,)+) {
+            type Refs<'r> = ($(&'r $G,)+);
+            type RefsMut<'r> = ($(&'r mut $G,)+);
+
+            fn types() -> Vec<(TypeId, &'static str)> {
+                vec![$((TypeId::of::<$G>(), type_name::<$G>())),+]
+            }
+
+            fn for_each_in<V: for<'r> FnMut(usize, Self::Refs<'r>)>(table: &Table, visit: &mut V) {
+                $(let $column = table.column::<$G>().unwrap();)+
+
+                for (row, entity) in table.entities.iter().enumerate() {
+                    visit(*entity, ($(&$column[row],)+));
+                }
+            }
+
+            // Every column is handed out once, as the query has already been checked for
+            // duplicates
+            fn for_each_mut_in<V: for<'r> FnMut(usize, Self::RefsMut<'r>)>(
+                table: &mut Table,
+                visit: &mut V,
+            ) {
+                $(let mut $column: Option<&mut Vec<$G>> = None;)+
+
+                for column in table.columns.iter_mut() {
+                    let column = column.as_any_mut();
+                    $(if column.is::<Vec<$G>>() {
+                        $column = column.downcast_mut::<Vec<$G>>();
+                        continue;
+                    })+
+                }
+
+                $(let $column = $column.unwrap();)+
+
+                for (row, entity) in table.entities.iter().enumerate() {
+                    visit(*entity, ($(&mut $column[row],)+));
+                }
+            }
+        }
+    };
+}
+
+column_query!(A a);
+column_query!(A a, B b);
+column_query!(A a, B b, C c);
+column_query!(A a, B b, C c, D d);
+column_query!(A a, B b, C c, D d, E e);
+column_query!(A a, B b, C c, D d, E e, F f);
+
+#[cfg(test)]
+mod tests {
+    use crate::life::{colony::Archetypes, glitch::LifeError, Scene};
+
+    #[test]
+    fn adding_components_moves_the_entity_between_tables() {
+        let mut scene: Scene<Archetypes> = Scene::new();
+
+        let first = scene.entity();
+        let second = scene.entity();
+        scene.component(first, 1_i32);
+        scene.component(second, 2_i32);
+        scene.component(first, 1.5_f32);
+
+        assert_eq!(scene.component_pools.tables().len(), 2);
+        assert_eq!(scene.component_pools.location(first), Some((1, 0)));
+        assert_eq!(scene.component_pools.location(second), Some((0, 0)));
+        assert_eq!(scene.get_component::<i32>(first), Ok(&1));
+        assert_eq!(scene.get_component::<f32>(first), Ok(&1.5));
+        assert_eq!(scene.get_component::<i32>(second), Ok(&2));
+        assert_eq!(
+            scene.get_component::<f32>(second),
+            Err(LifeError::EntityNotBoundToComponent("f32", second))
+        );
+    }
+
+    #[test]
+    fn taking_and_deleting_keep_the_other_rows_in_place() {
+        let mut scene: Scene<Archetypes> = Scene::new();
+
+        let entities = (0..3).map(|_| scene.entity()).collect::<Vec<usize>>();
+        for entity in entities.iter() {
+            scene.component(*entity, *entity as i32);
+            scene.component(*entity, *entity as f32);
+        }
+
+        assert_eq!(scene.take_component::<i32>(entities[0]), Ok(0));
+        scene.delete(entities[1]);
+
+        assert_eq!(scene.get_component::<f32>(entities[0]), Ok(&0.0));
+        assert_eq!(scene.get_component::<i32>(entities[2]), Ok(&2));
+        assert_eq!(scene.get_component::<f32>(entities[2]), Ok(&2.0));
+        assert_eq!(
+            scene.get_component::<f32>(entities[1]),
+            Err(LifeError::EntityNotBoundToComponent("f32", entities[1]))
+        );
+        assert_eq!(
+            scene.try_delete(entities[1]),
+            Err(LifeError::EntityNotAlive(entities[1]))
+        );
+    }
+
+    #[test]
+    fn queries_only_visit_entities_holding_every_component() {
+        let mut scene: Scene<Archetypes> = Scene::new();
+
+        let moving = scene.entity();
+        scene.component(moving, 1_i32);
+        scene.component(moving, 2_u8);
+        scene.component(moving, 0.5_f32);
+
+        let still = scene.entity();
+        scene.component(still, 3_i32);
+
+        scene
+            .query_mut::<(i32, f32), _>(|_entity, (number, speed)| *number += (*speed * 2.0) as i32)
+            .unwrap();
+
+        let mut visited = Vec::new();
+        scene.query::<(i32,), _>(|entity, (number,)| visited.push((entity, *number)));
+
+        assert_eq!(visited, vec![(still, 3), (moving, 2)]);
+    }
+
+    #[test]
+    fn components_can_only_be_attached_to_live_entities() {
+        let mut scene: Scene<Archetypes> = Scene::new();
+
+        let entity = scene.entity();
+        scene.delete(entity);
+
+        assert_eq!(
+            scene.try_component(entity, 1_i32),
+            Err(LifeError::EntityNotAlive(entity))
+        );
+        assert_eq!(
+            scene.try_component(7, 1_i32),
+            Err(LifeError::EntityNotAlive(7))
+        );
+
+        assert_eq!(scene.entity(), entity);
+        assert_eq!(scene.component_pools.location(entity), None);
+    }
+
+    #[test]
+    fn querying_a_component_twice_fails() {
+        let mut scene: Scene<Archetypes> = Scene::new();
+
+        assert_eq!(
+            scene.query_mut::<(i32, i32), _>(|_entity, _components| {}),
+            Err(LifeError::ComponentBorrowedTwice("i32"))
+        );
+    }
+}
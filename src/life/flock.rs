@@ -0,0 +1,82 @@
+use std::any::type_name;
+
+use crate::life::{glitch::LifeError, Scene};
+
+impl<P> Scene<P> {
+    pub fn spawn_bundle<B: Bundle<P>>(&mut self, bundle: B) -> usize {
+        let entity = self.entity();
+        bundle.insert_into(self, entity);
+
+        entity
+    }
+
+    pub fn insert_bundle<B: Bundle<P>>(&mut self, entity: usize, bundle: B) {
+        bundle.insert_into(self, entity);
+    }
+
+    pub fn take_bundle<B: Bundle<P>>(&mut self, entity: usize) -> Result<B, LifeError> {
+        let missing = B::missing_from(self, entity);
+
+        if !missing.is_empty() {
+            return Err(LifeError::IncompleteBundle(
+                type_name::<B>(),
+                entity,
+                missing,
+            ));
+        }
+
+        B::take_from(self, entity)
+    }
+}
+
+pub trait Bundle<P>: Sized {
+    fn insert_into(self, scene: &mut Scene<P>, entity: usize);
+    fn missing_from(scene: &Scene<P>, entity: usize) -> Vec<&'static str>;
+    fn take_from(scene: &mut Scene<P>, entity: usize) -> Result<Self, LifeError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        life::{glitch::LifeError, Scene},
+        ComponentPools, Int,
+    };
+    use component::Bundle;
+
+    #[derive(Bundle, Debug)]
+    struct Counter {
+        count: Int,
+    }
+
+    #[test]
+    fn spawning_a_bundle_assigns_every_component() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        let entity = scene.spawn_bundle(Counter { count: Int(3) });
+
+        assert_eq!(scene.get_component::<Int>(entity).unwrap().0, 3);
+    }
+
+    #[test]
+    fn taking_a_bundle_removes_every_component() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        let entity = scene.entity();
+        scene.insert_bundle(entity, Counter { count: Int(5) });
+
+        assert_eq!(scene.take_bundle::<Counter>(entity).unwrap().count.0, 5);
+        assert!(scene.get_component::<Int>(entity).is_err());
+    }
+
+    #[test]
+    fn taking_an_incomplete_bundle_lists_missing_components() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        let entity = scene.entity();
+
+        match scene.take_bundle::<Counter>(entity) {
+            Err(LifeError::IncompleteBundle(_, 0, missing)) => assert_eq!(missing, vec!["Int"]),
+            other => panic!("expected an incomplete bundle error, got {:?}", other),
+        }
+    }
+}
@@ -1,17 +1,35 @@
 pub mod component;
 pub mod entity;
 
-use crate::life::{ComponentPool, ComponentPoolsTrait, Scene};
+use std::collections::HashMap;
 
-impl<P: ComponentPoolsTrait> Scene<P> {
+use crate::life::{glitch::ErrorPolicy, ComponentPool, Scene, Storage};
+
+impl<P: Storage> Scene<P> {
     pub fn new() -> Self {
+        Self::with_error_policy(ErrorPolicy::default())
+    }
+
+    pub fn with_error_policy(error_policy: ErrorPolicy) -> Self {
         Self {
             available_entities: vec![0],
-            component_pools: P::new(),
+            alive: Vec::new(),
+            component_pools: P::empty(),
+
+            disabled_pools: HashMap::new(),
+
+            error_policy,
+            errors: Vec::new(),
         }
     }
 }
 
+impl<P: Storage> Default for Scene<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<G> ComponentPool<G> {
     pub fn empty() -> Self {
         Self {
@@ -1,22 +1,52 @@
-use crate::life::{Component, ComponentPool, PoolToolbox, Scene};
+use crate::life::{
+    glitch::LifeError, Component, ComponentPool, ComponentPoolsTrait, PoolToolbox, Scene,
+};
 
-impl<P> Scene<P> {
+impl<P: ComponentPoolsTrait> Scene<P> {
     pub fn component<G: 'static + Component<P>>(&mut self, entity: usize, component: G) {
-        self.get_mut::<G>().assign_component(entity, component);
+        let result = self.try_component(entity, component);
+        self.handle(result);
+    }
+
+    pub fn try_component<G: 'static + Component<P>>(
+        &mut self,
+        entity: usize,
+        component: G,
+    ) -> Result<(), LifeError> {
+        // It would outlive the entity, and be handed over with its id once recycled
+        if !self.is_alive(entity) {
+            return Err(LifeError::EntityNotAlive(entity));
+        }
+
+        self.get_mut::<G>().try_assign_component(entity, component)
     }
 }
 
 impl<G: 'static> ComponentPool<G> {
     pub fn assign_component(&mut self, entity: usize, component: G) {
+        unwrap!(self.try_assign_component(entity, component));
+    }
+
+    pub fn try_assign_component(&mut self, entity: usize, component: G) -> Result<(), LifeError> {
         if self.has_component(entity) {
-            *unwrap!(self.get_mut(entity)) = component;
+            *self.get_mut(entity)? = component;
         } else {
-            Self::add_entity_to_sparse_array(entity, self.num_components, &mut self.sparse_array);
+            let id = self.packed_array.len();
+            Self::add_entity_to_sparse_array(entity, id, &mut self.sparse_array);
 
             self.packed_array.push(entity);
             self.component_array.push(component);
+
+            // New components are enabled, so they take the place of the first disabled one
+            if id != self.num_components {
+                self.try_swap_components(id, self.num_components)?;
+            }
+
             self.num_components += 1;
         }
+
+        self.check_invariants();
+        Ok(())
     }
 }
 /*
@@ -13,11 +13,23 @@ impl<P> Scene<P> {
         let id = self.available_entities[0];
         self.available_entities[0] += 1;
 
+        self.bring_to_life(id);
         id
     }
 
     pub fn use_recycled_entity(&mut self) -> usize {
-        self.available_entities.pop().unwrap()
+        let id = self.available_entities.pop().unwrap();
+
+        self.bring_to_life(id);
+        id
+    }
+
+    fn bring_to_life(&mut self, entity: usize) {
+        if self.alive.len() <= entity {
+            self.alive.resize(entity + 1, false);
+        }
+
+        self.alive[entity] = true;
     }
 }
 
@@ -1,16 +1,83 @@
-use crate::life::{gizmos::PoolToolbox, Component, ComponentPool, Scene};
+use std::any::type_name;
+
+use crate::life::{
+    gizmos::PoolToolbox, glitch::LifeError, Component, ComponentPool, ComponentPoolsTrait, Scene,
+};
 
 impl<P> Scene<P> {
     pub fn toggle<G: 'static + Component<P>>(&mut self, entity: usize) {
-        self.get_mut::<G>().toggle_entity(entity);
+        let result = self.try_toggle::<G>(entity);
+        self.handle(result);
     }
 
     pub fn enable<G: 'static + Component<P>>(&mut self, entity: usize) {
-        self.get_mut::<G>().enable_entity(entity);
+        let result = self.try_enable::<G>(entity);
+        self.handle(result);
     }
 
     pub fn disable<G: 'static + Component<P>>(&mut self, entity: usize) {
-        self.get_mut::<G>().disable_entity(entity);
+        let result = self.try_disable::<G>(entity);
+        self.handle(result);
+    }
+
+    pub fn try_toggle<G: 'static + Component<P>>(
+        &mut self,
+        entity: usize,
+    ) -> Result<(), LifeError> {
+        self.get_mut::<G>().try_toggle_entity(entity)
+    }
+
+    pub fn try_enable<G: 'static + Component<P>>(
+        &mut self,
+        entity: usize,
+    ) -> Result<(), LifeError> {
+        self.get_mut::<G>().try_enable_entity(entity)
+    }
+
+    pub fn try_disable<G: 'static + Component<P>>(
+        &mut self,
+        entity: usize,
+    ) -> Result<(), LifeError> {
+        self.get_mut::<G>().try_disable_entity(entity)
+    }
+}
+
+// Whole entities, in every pool they have a component in. Disabling leaves the components that
+// already are as they are, and enabling only brings back the ones it disabled, so a component
+// disabled on its own stays so
+impl<P: ComponentPoolsTrait> Scene<P> {
+    pub fn enable_entity(&mut self, entity: usize) {
+        let result = self.try_enable_entity(entity);
+        self.handle(result);
+    }
+
+    pub fn disable_entity(&mut self, entity: usize) {
+        let result = self.try_disable_entity(entity);
+        self.handle(result);
+    }
+
+    pub fn try_enable_entity(&mut self, entity: usize) -> Result<(), LifeError> {
+        if !self.is_alive(entity) {
+            return Err(LifeError::EntityNotAlive(entity));
+        }
+
+        let disabled = self.disabled_pools.remove(&entity).unwrap_or_default();
+        self.component_pools.enable_entity(entity, &disabled)
+    }
+
+    pub fn try_disable_entity(&mut self, entity: usize) -> Result<(), LifeError> {
+        if !self.is_alive(entity) {
+            return Err(LifeError::EntityNotAlive(entity));
+        }
+
+        let mut disabled = Vec::new();
+        let result = self.component_pools.disable_entity(entity, &mut disabled);
+
+        self.disabled_pools
+            .entry(entity)
+            .or_default()
+            .extend(disabled);
+        result
     }
 }
 
@@ -20,39 +87,97 @@ pub trait ComponentToggler {
     fn disable_entity(&mut self, entity: usize);
     fn is_disabled(&mut self, entity: usize) -> bool;
     fn move_to_back(&mut self, entity: usize);
+
+    fn try_toggle_entity(&mut self, entity: usize) -> Result<(), LifeError>;
+    fn try_enable_entity(&mut self, entity: usize) -> Result<(), LifeError>;
+    fn try_disable_entity(&mut self, entity: usize) -> Result<(), LifeError>;
+    fn try_move_to_back(&mut self, entity: usize) -> Result<(), LifeError>;
+
+    fn try_enable_if_disabled(&mut self, entity: usize) -> Result<bool, LifeError>;
+    fn try_disable_if_enabled(&mut self, entity: usize) -> Result<bool, LifeError>;
 }
 
 impl<G: 'static> ComponentToggler for ComponentPool<G> {
     fn toggle_entity(&mut self, entity: usize) {
+        unwrap!(self.try_toggle_entity(entity));
+    }
+
+    fn disable_entity(&mut self, entity: usize) {
+        unwrap!(self.try_disable_entity(entity));
+    }
+
+    fn enable_entity(&mut self, entity: usize) {
+        unwrap!(self.try_enable_entity(entity));
+    }
+
+    fn try_toggle_entity(&mut self, entity: usize) -> Result<(), LifeError> {
+        self.component_id(entity)?;
+
         if self.is_disabled(entity) {
-            self.disable_entity(entity);
+            self.try_enable_entity(entity)
         } else {
-            self.enable_entity(entity);
+            self.try_disable_entity(entity)
         }
     }
 
-    fn disable_entity(&mut self, entity: usize) {
-        self.move_to_back(entity);
+    fn try_disable_entity(&mut self, entity: usize) -> Result<(), LifeError> {
+        if self.component_id(entity)? >= self.num_components {
+            return Err(LifeError::EntityAlreadyDisabled(type_name::<G>(), entity));
+        }
+
+        self.try_move_to_back(entity)?;
         self.num_components -= 1;
+
+        self.check_invariants();
+        Ok(())
     }
 
-    fn enable_entity(&mut self, entity: usize) {
+    fn try_enable_entity(&mut self, entity: usize) -> Result<(), LifeError> {
+        if self.component_id(entity)? < self.num_components {
+            return Err(LifeError::EntityAlreadyEnabled(type_name::<G>(), entity));
+        }
+
         self.num_components += 1;
-        self.move_to_back(entity);
+        self.try_move_to_back(entity)?;
+
+        self.check_invariants();
+        Ok(())
+    }
+
+    // Used on the pools when a whole entity is enabled or disabled, whether it has a component
+    // in them or not. Returns whether the component got toggled
+    fn try_enable_if_disabled(&mut self, entity: usize) -> Result<bool, LifeError> {
+        match self.has_component(entity) && self.is_disabled(entity) {
+            true => self.try_enable_entity(entity).map(|_| true),
+            false => Ok(false),
+        }
+    }
+
+    fn try_disable_if_enabled(&mut self, entity: usize) -> Result<bool, LifeError> {
+        match self.has_component(entity) && !self.is_disabled(entity) {
+            true => self.try_disable_entity(entity).map(|_| true),
+            false => Ok(false),
+        }
     }
 
     /* Utility functions */
 
     fn is_disabled(&mut self, entity: usize) -> bool {
-        (self.sparse_array[entity] as usize) < self.num_components
+        (self.sparse_array[entity] as usize) >= self.num_components
     }
 
     fn move_to_back(&mut self, entity: usize) {
-        let component = unwrap!(self.component_id(entity));
+        unwrap!(self.try_move_to_back(entity));
+    }
+
+    fn try_move_to_back(&mut self, entity: usize) -> Result<(), LifeError> {
+        let component = self.component_id(entity)?;
         let component_destination = self.num_components - 1;
 
-        let entity_destination = unwrap!(self.entity_id(component_destination));
+        let entity_destination = self.entity_id(component_destination)?;
 
         self.swap(entity, entity_destination, component, component_destination);
+
+        Ok(())
     }
 }
@@ -31,6 +31,17 @@ pub trait PoolToolbox {
     fn swap_entities(&mut self, entity: usize, entity_destination: usize);
     fn swap_components(&mut self, component: usize, component_destination: usize);
     fn swap(&mut self, entt: usize, entt_dest: usize, comp: usize, comp_dest: usize);
+
+    fn try_swap_entities(
+        &mut self,
+        entity: usize,
+        entity_destination: usize,
+    ) -> Result<(), LifeError>;
+    fn try_swap_components(
+        &mut self,
+        component: usize,
+        component_destination: usize,
+    ) -> Result<(), LifeError>;
 }
 
 impl<G: 'static> PoolToolbox for ComponentPool<G> {
@@ -41,7 +52,7 @@ impl<G: 'static> PoolToolbox for ComponentPool<G> {
     fn entity_id(&self, component_id: usize) -> Result<usize, LifeError> {
         self.packed_array
             .get(component_id)
-            .map(|x| x.clone())
+            .copied()
             .ok_or(LifeError::ComponentNotFound(type_name::<G>(), component_id))
     }
 
@@ -52,22 +63,44 @@ impl<G: 'static> PoolToolbox for ComponentPool<G> {
                 type_name::<G>(),
                 entity_id,
             )),
-            Some(id) => Ok(id.clone() as usize),
+            Some(id) => Ok(*id as usize),
         }
     }
 
     fn swap_entities(&mut self, entity: usize, entity_destination: usize) {
-        let component = unwrap!(self.component_id(entity));
-        let component_destination = unwrap!(self.component_id(entity_destination));
+        unwrap!(self.try_swap_entities(entity, entity_destination));
+    }
+
+    fn swap_components(&mut self, component: usize, component_destination: usize) {
+        unwrap!(self.try_swap_components(component, component_destination));
+    }
+
+    fn try_swap_entities(
+        &mut self,
+        entity: usize,
+        entity_destination: usize,
+    ) -> Result<(), LifeError> {
+        let component = self.component_id(entity)?;
+        let component_destination = self.component_id(entity_destination)?;
 
         self.swap(entity, entity_destination, component, component_destination);
+
+        self.check_invariants();
+        Ok(())
     }
 
-    fn swap_components(&mut self, component: usize, component_destination: usize) {
-        let entity = unwrap!(self.entity_id(component));
-        let entity_destination = unwrap!(self.entity_id(component_destination));
+    fn try_swap_components(
+        &mut self,
+        component: usize,
+        component_destination: usize,
+    ) -> Result<(), LifeError> {
+        let entity = self.entity_id(component)?;
+        let entity_destination = self.entity_id(component_destination)?;
 
         self.swap(entity, entity_destination, component, component_destination);
+
+        self.check_invariants();
+        Ok(())
     }
 
     fn swap(&mut self, entt: usize, entt_dest: usize, comp: usize, comp_dest: usize) {
@@ -86,7 +119,7 @@ mod tests {
         let mut pool = ComponentPool::empty();
         pool.assign_component(3, 32);
 
-        assert_eq!(pool.has_component(4), false);
+        assert!(!pool.has_component(4));
     }
 
     #[test]
@@ -94,7 +127,7 @@ mod tests {
         let mut pool = ComponentPool::empty();
         pool.assign_component(3, 32);
 
-        assert_eq!(pool.has_component(2), false);
+        assert!(!pool.has_component(2));
     }
 
     #[test]
@@ -102,6 +135,6 @@ mod tests {
         let mut pool = ComponentPool::empty();
         pool.assign_component(3, 32);
 
-        assert_eq!(pool.has_component(3), true);
+        assert!(pool.has_component(3));
     }
 }
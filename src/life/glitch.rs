@@ -1,4 +1,8 @@
+use std::mem;
+
 use thiserror::Error;
+
+use crate::life::Scene;
 /*
 #[cfg(test)]
 macro_rules! assert_err {
@@ -38,6 +42,102 @@ pub enum LifeError {
     ComponentNotFound(&'static str, usize),
     #[error("Entity {1} does not have any component bound to it for : {0}.")]
     EntityNotBoundToComponent(&'static str, usize),
-    #[error("Entity {1} is bound to a non existing component for : {0}. Note: this is supposed to be impossible, so there's probably a rather large bug in ignition")]
+    #[error("Entity {1} is bound to a non existing component for : {0}. Note: this is supposed to be impossible, `ComponentPool::validate()` should point out what got corrupted")]
     EntityBoundToNonExistingComponent(&'static str, usize),
+    #[error("Entity {1} is missing {2:?} to form the bundle : {0}.")]
+    IncompleteBundle(&'static str, usize, Vec<&'static str>),
+    #[error("Entity {1} is already enabled for : {0}.")]
+    EntityAlreadyEnabled(&'static str, usize),
+    #[error("Entity {1} is already disabled for : {0}.")]
+    EntityAlreadyDisabled(&'static str, usize),
+    #[error("Entity {0} is not alive, it has either never been created or already been deleted.")]
+    EntityNotAlive(usize),
+    #[error("ComponentPool<{0}> is borrowed more than once.")]
+    PoolBorrowedTwice(&'static str),
+    #[error("ComponentPool<{0}> doesn't lie within the component pools it was borrowed from. Note: this is supposed to be impossible, unless `Component` was implemented by hand")]
+    PoolOutsideOfPools(&'static str),
+    #[error("{0} is borrowed more than once by the same query.")]
+    ComponentBorrowedTwice(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    Panic,
+    #[default]
+    Log,
+    Ignore,
+    Collect,
+}
+
+impl<P> Scene<P> {
+    pub fn handle<T>(&mut self, result: Result<T, LifeError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                match self.error_policy {
+                    ErrorPolicy::Panic => panic!("{}", e),
+                    ErrorPolicy::Log => log::warn!("{}", e),
+                    ErrorPolicy::Ignore => {}
+                    ErrorPolicy::Collect => self.errors.push(e),
+                }
+
+                None
+            }
+        }
+    }
+
+    pub fn take_errors(&mut self) -> Vec<LifeError> {
+        mem::take(&mut self.errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        life::{glitch::ErrorPolicy, glitch::LifeError, Scene},
+        ComponentPools, Int,
+    };
+
+    #[test]
+    fn try_variants_return_errors_instead_of_logging() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        let entity = scene.entity();
+
+        assert_eq!(
+            scene.try_disable::<Int>(entity),
+            Err(LifeError::EntityNotFound("ignition::Int", 0))
+        );
+    }
+
+    #[test]
+    fn collect_policy_stores_errors_of_non_try_variants() {
+        let mut scene: Scene<ComponentPools> = Scene::with_error_policy(ErrorPolicy::Collect);
+
+        let entity = scene.entity();
+        scene.component(entity, Int(1));
+        scene.enable::<Int>(entity);
+        scene.disable::<Int>(entity);
+        scene.disable::<Int>(entity);
+
+        assert_eq!(
+            scene.take_errors(),
+            vec![
+                LifeError::EntityAlreadyEnabled("ignition::Int", 0),
+                LifeError::EntityAlreadyDisabled("ignition::Int", 0),
+            ]
+        );
+        assert!(scene.errors.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "is already disabled")]
+    fn panic_policy_panics_on_error() {
+        let mut scene: Scene<ComponentPools> = Scene::with_error_policy(ErrorPolicy::Panic);
+
+        let entity = scene.entity();
+        scene.component(entity, Int(1));
+        scene.disable::<Int>(entity);
+        scene.disable::<Int>(entity);
+    }
 }
@@ -0,0 +1,211 @@
+use std::{
+    any::{type_name, TypeId},
+    mem::size_of,
+};
+
+use crate::life::{glitch::LifeError, Component, ComponentPool, ComponentPoolsTrait, Scene};
+
+/* Mutable borrows of several pools at once, so that one can be updated from another :
+        let (positions, velocities) = scene.get_many_mut::<(Position, Velocity)>()?;
+
+   Each pool is looked up on its own through `Component::get_mut_from()`, which only tells where
+   it lives inside the pools. They are then all borrowed again from there, which is sound as long
+   as they lie within the pools and don't overlap. The unsafe `Component` trait promises as much,
+   and the checks before handing them out catch what a faulty implementation would get wrong.
+   `ComponentPools::split()` does the same for every pool without any check, by borrowing fields
+*/
+impl<P> Scene<P> {
+    pub fn get_many_mut<'a, T: PoolTuple<'a, P>>(&'a mut self) -> Result<T::PoolsMut, LifeError> {
+        T::get_many_mut_from(&mut self.component_pools)
+    }
+}
+
+/* Queries walk the pool of the first component and look the entity up in the others, so the
+   rarest component is best put first. Disabled components are skipped :
+        scene.query_mut::<(Position, Velocity), _>(|entity, (position, velocity)| ...)?;
+
+   Same calls as on `Scene<Archetypes>`, which is why they're limited to pools
+*/
+impl<P: ComponentPoolsTrait> Scene<P> {
+    pub fn query<Q: PoolQuery<P>, F: for<'r> FnMut(usize, Q::Refs<'r>)>(&self, f: F) {
+        Q::for_each_in(&self.component_pools, f)
+    }
+
+    pub fn query_mut<Q: PoolQuery<P>, F: for<'r> FnMut(usize, Q::RefsMut<'r>)>(
+        &mut self,
+        f: F,
+    ) -> Result<(), LifeError> {
+        Q::for_each_mut_in(&mut self.component_pools, f)
+    }
+}
+
+pub trait PoolTuple<'a, P> {
+    type PoolsMut;
+
+    fn get_many_mut_from(component_pools: &'a mut P) -> Result<Self::PoolsMut, LifeError>;
+}
+
+// Range of bytes taken by the pool of `G` inside the pools
+fn locate<P, G: 'static + Component<P>>(
+    component_pools: &mut P,
+) -> Result<(usize, usize), LifeError> {
+    let start = component_pools as *mut P as usize;
+    let pool = G::get_mut_from(component_pools) as *mut ComponentPool<G> as usize;
+
+    let offset = pool.wrapping_sub(start);
+    match offset.checked_add(size_of::<ComponentPool<G>>()) {
+        Some(end) if end <= size_of::<P>() => Ok((offset, end)),
+        _ => Err(LifeError::PoolOutsideOfPools(type_name::<G>())),
+    }
+}
+
+fn check_disjoint(pools: &[(TypeId, &'static str, (usize, usize))]) -> Result<(), LifeError> {
+    for (i, (id, name, (start, end))) in pools.iter().enumerate() {
+        for (other_id, _other_name, (other_start, other_end)) in pools[..i].iter() {
+            if id == other_id || (start < other_end && other_start < end) {
+                return Err(LifeError::PoolBorrowedTwice(name));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+macro_rules! pool_tuple {
+    ($($G:ident),+) => {
+        impl<'a, P, $($G: 'static + Component<P>),+> PoolTuple<'a, P> for ($($G,)+) {
+            type PoolsMut = ($(&'a mut ComponentPool<$G>,)+);
+
+            fn get_many_mut_from(component_pools: &'a mut P) -> Result<Self::PoolsMut, LifeError> {
+                let pools = [$(
+                    (TypeId::of::<$G>(), type_name::<$G>(), locate::<P, $G>(component_pools)?),
+                )+];
+                check_disjoint(&pools)?;
+
+                let start = component_pools as *mut P as *mut u8;
+                let mut offsets = pools.iter().map(|(_id, _name, (offset, _end))| *offset);
+
+                // Every pool is one of the pools in `component_pools`, which is borrowed for 'a, as
+                // `Component` promises, and none of them overlap
+                Ok(($(unsafe {
+                    &mut *(start.add(offsets.next().unwrap()) as *mut ComponentPool<$G>)
+                },)+))
+            }
+        }
+    };
+}
+
+pool_tuple!(A);
+pool_tuple!(A, B);
+pool_tuple!(A, B, C);
+pool_tuple!(A, B, C, D);
+pool_tuple!(A, B, C, D, E);
+pool_tuple!(A, B, C, D, E, F);
+
+pub trait PoolQuery<P> {
+    type Refs<'r>;
+    type RefsMut<'r>;
+
+    fn for_each_in<V: for<'r> FnMut(usize, Self::Refs<'r>)>(component_pools: &P, visit: V);
+    fn for_each_mut_in<V: for<'r> FnMut(usize, Self::RefsMut<'r>)>(
+        component_pools: &mut P,
+        visit: V,
+    ) -> Result<(), LifeError>;
+}
+
+fn enabled_id<G>(pool: &ComponentPool<G>, entity: usize) -> Option<usize> {
+    match pool.sparse_array.get(entity) {
+        Some(&id) if id >= 0 && (id as usize) < pool.num_components => Some(id as usize),
+        _ => None,
+    }
+}
+
+macro_rules! pool_query {
+    ($First:ident $first:ident $(, $G:ident $pool:ident)*) => {
+        impl<P, $First: 'static + Component<P>, $($G: 'static + Component<P>),*> PoolQuery<P>
+            for ($First, $($G,)*)
+        {
+            type Refs<'r> = (&'r $First, $(&'r $G,)*);
+            type RefsMut<'r> = (&'r mut $First, $(&'r mut $G,)*);
+
+            fn for_each_in<V: for<'r> FnMut(usize, Self::Refs<'r>)>(component_pools: &P, mut visit: V) {
+                let $first = $First::get_from(component_pools);
+                $(let $pool = $G::get_from(component_pools);)*
+
+                for id in 0..$first.num_components {
+                    let entity = $first.packed_array[id];
+
+                    visit(entity, (&$first.component_array[id], $(match enabled_id($pool, entity) {
+                        Some(id) => &$pool.component_array[id],
+                        None => continue,
+                    },)*));
+                }
+            }
+
+            fn for_each_mut_in<V: for<'r> FnMut(usize, Self::RefsMut<'r>)>(
+                component_pools: &mut P,
+                mut visit: V,
+            ) -> Result<(), LifeError> {
+                let ($first, $($pool,)*) =
+                    <($First, $($G,)*) as PoolTuple<'_, P>>::get_many_mut_from(component_pools)?;
+
+                for id in 0..$first.num_components {
+                    let entity = $first.packed_array[id];
+
+                    visit(entity, (&mut $first.component_array[id], $(match enabled_id($pool, entity) {
+                        Some(id) => &mut $pool.component_array[id],
+                        None => continue,
+                    },)*));
+                }
+
+                Ok(())
+            }
+        }
+    };
+}
+
+pool_query!(A a);
+pool_query!(A a, B b);
+pool_query!(A a, B b, C c);
+pool_query!(A a, B b, C c, D d);
+pool_query!(A a, B b, C c, D d, E e);
+pool_query!(A a, B b, C c, D d, E e, F f);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        life::{glitch::LifeError, Scene},
+        ComponentPools, Int,
+    };
+
+    #[test]
+    fn borrowing_a_pool_twice_fails() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        assert_eq!(
+            scene.get_many_mut::<(Int, Int)>().err(),
+            Some(LifeError::PoolBorrowedTwice("ignition::Int"))
+        );
+    }
+
+    #[test]
+    fn queries_skip_disabled_components() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        let entities = (0..3).map(|_| scene.entity()).collect::<Vec<usize>>();
+        for entity in entities.iter() {
+            scene.component(*entity, Int(*entity as i32));
+        }
+        scene.disable::<Int>(entities[1]);
+
+        scene
+            .query_mut::<(Int,), _>(|_entity, (int,)| int.0 += 10)
+            .unwrap();
+
+        let mut visited = Vec::new();
+        scene.query::<(Int,), _>(|entity, (int,)| visited.push((entity, int.0)));
+
+        assert_eq!(visited, vec![(entities[0], 10), (entities[2], 12)]);
+        assert_eq!(scene.get_component::<Int>(entities[1]).map(|x| x.0), Ok(1));
+    }
+}
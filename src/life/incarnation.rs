@@ -0,0 +1,113 @@
+use crate::life::{glitch::LifeError, Component, ComponentPoolsTrait, Scene};
+
+/* Views over a single entity, so that it doesn't have to be repeated on every call :
+        scene.entity_mut(player).insert(Position(0, 0)).insert(Health(100));
+
+   They stand in for `scene[entity]`, which isn't planned : `Index` can only hand out references to
+   something the scene already owns, which a view built on the fly isn't.
+*/
+pub struct EntityRef<'a, P> {
+    scene: &'a Scene<P>,
+    entity: usize,
+}
+
+pub struct EntityMut<'a, P> {
+    scene: &'a mut Scene<P>,
+    entity: usize,
+}
+
+impl<P> Scene<P> {
+    pub fn entity_ref(&self, entity: usize) -> EntityRef<'_, P> {
+        EntityRef {
+            scene: self,
+            entity,
+        }
+    }
+
+    pub fn entity_mut(&mut self, entity: usize) -> EntityMut<'_, P> {
+        EntityMut {
+            scene: self,
+            entity,
+        }
+    }
+}
+
+impl<'a, P: ComponentPoolsTrait> EntityRef<'a, P> {
+    pub fn id(&self) -> usize {
+        self.entity
+    }
+
+    pub fn get<G: 'static + Component<P>>(&self) -> Result<&'a G, LifeError> {
+        self.scene.get_component::<G>(self.entity)
+    }
+
+    pub fn has<G: 'static + Component<P>>(&self) -> bool {
+        self.get::<G>().is_ok()
+    }
+}
+
+impl<'a, P: ComponentPoolsTrait> EntityMut<'a, P> {
+    pub fn id(&self) -> usize {
+        self.entity
+    }
+
+    pub fn get<G: 'static + Component<P>>(&self) -> Result<&G, LifeError> {
+        self.scene.get_component::<G>(self.entity)
+    }
+
+    pub fn get_mut<G: 'static + Component<P>>(&mut self) -> Result<&mut G, LifeError> {
+        self.scene.get_component_mut::<G>(self.entity)
+    }
+
+    pub fn has<G: 'static + Component<P>>(&self) -> bool {
+        self.get::<G>().is_ok()
+    }
+
+    pub fn insert<G: 'static + Component<P>>(&mut self, component: G) -> &mut Self {
+        self.scene.component(self.entity, component);
+        self
+    }
+
+    pub fn remove<G: 'static + Component<P>>(&mut self) -> Result<G, LifeError> {
+        self.scene.take_component::<G>(self.entity)
+    }
+
+    pub fn despawn(self) {
+        self.scene.delete(self.entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{life::Scene, ComponentPools, Int};
+
+    #[test]
+    fn entity_mut_forwards_to_the_scene() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        let entity = scene.entity();
+        scene.entity_mut(entity).insert(Int(2));
+
+        let mut view = scene.entity_mut(entity);
+        view.get_mut::<Int>().unwrap().0 += 1;
+
+        assert!(view.has::<Int>());
+        assert_eq!(view.remove::<Int>().unwrap().0, 3);
+        assert!(!scene.entity_ref(entity).has::<Int>());
+    }
+
+    #[test]
+    fn despawned_entities_lose_their_components() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        let entity = scene.entity();
+        scene.component(entity, Int(7));
+
+        assert_eq!(scene.entity_ref(entity).get::<Int>().unwrap().0, 7);
+
+        scene.entity_mut(entity).despawn();
+
+        assert!(scene.entity_ref(entity).get::<Int>().is_err());
+        assert_eq!(scene.entity(), entity);
+    }
+}
@@ -0,0 +1,343 @@
+use std::{any::type_name, collections::HashSet, fmt, fmt::Debug};
+
+use crate::life::{census::PoolVisitor, ComponentPool, ComponentPoolsTrait, Scene};
+
+impl<P: ComponentPoolsTrait> Scene<P> {
+    pub fn validate(&self) -> SceneReport {
+        let mut visitor = ValidationVisitor::default();
+        self.component_pools.visit_pools(&mut visitor);
+
+        let mut violations = Vec::new();
+        let mut recycled = HashSet::new();
+
+        for &entity in self.available_entities[1..].iter() {
+            if entity >= self.available_entities[0] {
+                violations.push(Violation::RecycledEntityNeverCreated { entity });
+            }
+
+            if !recycled.insert(entity) {
+                violations.push(Violation::DuplicateRecycledEntity { entity });
+            }
+
+            if self.is_alive(entity) {
+                violations.push(Violation::RecycledEntityAlive { entity });
+            }
+        }
+
+        for (pool, entity) in visitor.entities {
+            if !self.is_alive(entity) {
+                violations.push(Violation::ComponentOfDeadEntity { entity, pool });
+            }
+        }
+
+        SceneReport {
+            violations,
+            pools: visitor.reports,
+        }
+    }
+}
+
+impl<G: 'static> ComponentPool<G> {
+    pub fn validate(&self) -> PoolReport {
+        let mut violations = Vec::new();
+
+        let len = self.packed_array.len();
+
+        if self.component_array.len() != len {
+            violations.push(Violation::LengthMismatch {
+                packed: len,
+                components: self.component_array.len(),
+            });
+        }
+
+        if self.num_components > len {
+            violations.push(Violation::TooManyEnabled {
+                num_components: self.num_components,
+                len,
+            });
+        }
+
+        let mut entities = HashSet::new();
+
+        for (component, &entity) in self.packed_array.iter().enumerate() {
+            if !entities.insert(entity) {
+                violations.push(Violation::DuplicateEntity { entity });
+            }
+
+            let sparse = self.sparse_array.get(entity).cloned().unwrap_or(-1);
+            if sparse != component as i32 {
+                violations.push(Violation::BrokenRoundTrip {
+                    entity,
+                    component,
+                    sparse,
+                });
+            }
+        }
+
+        for (entity, &component) in self.sparse_array.iter().enumerate() {
+            let dangling = match component {
+                -1 => false,
+                c if c < 0 => true,
+                c => self.packed_array.get(c as usize) != Some(&entity),
+            };
+
+            if dangling {
+                violations.push(Violation::DanglingSparse { entity, component });
+            }
+        }
+
+        PoolReport {
+            pool: type_name::<G>(),
+            violations,
+        }
+    }
+
+    #[cfg(feature = "debug-invariants")]
+    pub(crate) fn check_invariants(&self) {
+        let report = self.validate();
+        assert!(report.is_valid(), "{}", report);
+    }
+
+    #[cfg(not(feature = "debug-invariants"))]
+    pub(crate) fn check_invariants(&self) {}
+}
+
+#[derive(Default)]
+struct ValidationVisitor {
+    reports: Vec<PoolReport>,
+    entities: Vec<(&'static str, usize)>,
+}
+
+impl PoolVisitor for ValidationVisitor {
+    fn visit<G: 'static + Debug>(&mut self, name: &'static str, pool: &ComponentPool<G>) {
+        self.reports.push(pool.validate());
+        self.entities
+            .extend(pool.packed_array.iter().map(|&entity| (name, entity)));
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    LengthMismatch {
+        packed: usize,
+        components: usize,
+    },
+    TooManyEnabled {
+        num_components: usize,
+        len: usize,
+    },
+    DuplicateEntity {
+        entity: usize,
+    },
+    BrokenRoundTrip {
+        entity: usize,
+        component: usize,
+        sparse: i32,
+    },
+    DanglingSparse {
+        entity: usize,
+        component: i32,
+    },
+    RecycledEntityNeverCreated {
+        entity: usize,
+    },
+    DuplicateRecycledEntity {
+        entity: usize,
+    },
+    RecycledEntityAlive {
+        entity: usize,
+    },
+    ComponentOfDeadEntity {
+        entity: usize,
+        pool: &'static str,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolReport {
+    pub pool: &'static str,
+    pub violations: Vec<Violation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SceneReport {
+    pub violations: Vec<Violation>,
+    pub pools: Vec<PoolReport>,
+}
+
+impl PoolReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl SceneReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty() && self.pools.iter().all(|x| x.is_valid())
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch { packed, components } => write!(
+                f,
+                "packed_array holds {} entities but component_array holds {} components",
+                packed, components
+            ),
+            Self::TooManyEnabled {
+                num_components,
+                len,
+            } => write!(
+                f,
+                "num_components is {} but only {} components exist",
+                num_components, len
+            ),
+            Self::DuplicateEntity { entity } => {
+                write!(
+                    f,
+                    "entity {} appears more than once in packed_array",
+                    entity
+                )
+            }
+            Self::BrokenRoundTrip {
+                entity,
+                component,
+                sparse,
+            } => write!(
+                f,
+                "packed_array[{}] is entity {} but sparse_array[{}] is {}",
+                component, entity, entity, sparse
+            ),
+            Self::DanglingSparse { entity, component } => write!(
+                f,
+                "sparse_array[{}] is {} which doesn't point back to entity {}",
+                entity, component, entity
+            ),
+            Self::RecycledEntityNeverCreated { entity } => {
+                write!(f, "entity {} is recycled but was never created", entity)
+            }
+            Self::DuplicateRecycledEntity { entity } => {
+                write!(f, "entity {} is recycled more than once", entity)
+            }
+            Self::RecycledEntityAlive { entity } => {
+                write!(f, "entity {} is recycled but still alive", entity)
+            }
+            Self::ComponentOfDeadEntity { entity, pool } => {
+                write!(f, "entity {} isn't alive but holds a {}", entity, pool)
+            }
+        }
+    }
+}
+
+impl fmt::Display for PoolReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.is_valid() {
+            true => writeln!(f, "ComponentPool<{}> is valid", self.pool)?,
+            false => writeln!(f, "ComponentPool<{}> is corrupted :", self.pool)?,
+        }
+
+        for violation in self.violations.iter() {
+            writeln!(f, "  - {}", violation)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for SceneReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for violation in self.violations.iter() {
+            writeln!(f, "Scene : {}", violation)?;
+        }
+
+        for pool in self.pools.iter() {
+            write!(f, "{}", pool)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        life::{vitals::Violation, ComponentPool, Scene},
+        ComponentPools, Int,
+    };
+
+    #[test]
+    fn consistent_pool_is_valid() {
+        let mut pool = ComponentPool::empty();
+        pool.assign_component(1, 32i32);
+        pool.assign_component(4, 21i32);
+
+        assert!(pool.validate().is_valid());
+    }
+
+    #[test]
+    fn corrupted_pool_reports_every_violation() {
+        let pool = ComponentPool {
+            num_components: 3,
+
+            sparse_array: vec![-1, 1, 0],
+            packed_array: vec![1, 1],
+            component_array: vec![32],
+        };
+
+        assert_eq!(
+            pool.validate().violations,
+            vec![
+                Violation::LengthMismatch {
+                    packed: 2,
+                    components: 1
+                },
+                Violation::TooManyEnabled {
+                    num_components: 3,
+                    len: 2
+                },
+                Violation::BrokenRoundTrip {
+                    entity: 1,
+                    component: 0,
+                    sparse: 1
+                },
+                Violation::DuplicateEntity { entity: 1 },
+                Violation::DanglingSparse {
+                    entity: 2,
+                    component: 0
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "debug-invariants")]
+    #[test]
+    #[should_panic(expected = "is corrupted")]
+    fn corruption_is_caught_by_the_next_mutation() {
+        let mut pool = ComponentPool::empty();
+        pool.assign_component(1, 32i32);
+
+        pool.sparse_array[1] = 5;
+
+        pool.assign_component(4, 21i32);
+    }
+
+    #[test]
+    fn components_of_dead_entities_are_reported() {
+        let mut scene: Scene<ComponentPools> = Scene::new();
+
+        let entity = scene.entity();
+        scene.delete(entity);
+
+        // Straight into the pool, the scene refuses to
+        scene.get_mut::<Int>().assign_component(entity, Int(1));
+
+        assert_eq!(
+            scene.validate().violations,
+            vec![Violation::ComponentOfDeadEntity {
+                entity,
+                pool: "Int"
+            }]
+        );
+    }
+}
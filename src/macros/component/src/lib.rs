@@ -1,34 +1,282 @@
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn;
+use syn::{self, parse_macro_input};
 
 use utils::{parsing::*, update_components};
 
-#[proc_macro_derive(Component)]
+// Components also have to implement Debug, for `Scene::dump()` to print them. engine!() and
+// ecs!() point at the components that don't :
+//      #[derive(Component, Debug)]
+//      pub struct Position(f32, f32);
+//
+// Components only ever listed by hand in engine!(components: [...]) can stay out of
+// components.toml, which then can't break their build :
+//      #[derive(Component, Debug)]
+//      #[component(no_registry)]
+//      pub struct Position(f32, f32);
+#[proc_macro_derive(Component, attributes(component))]
 pub fn component(input: TokenStream) -> TokenStream {
-    update_components();
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+
+    match expand_component(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand_component(ast: &syn::DeriveInput) -> syn::Result<TokenStream2> {
+    if !is_out_of_registry(ast)? {
+        update_components()
+            .map_err(|message| syn::Error::new(proc_macro2::Span::call_site(), message))?;
+    }
+
+    check_component_shape(ast)?;
 
-    let ast: syn::DeriveInput = syn::parse(input.clone()).unwrap();
     let component_type = ast.ident.clone();
-    let component_trait = to_ident(&format!("{}Trait", component_type));
+    let component_module = to_accessor_module(&component_type);
     let component_name = to_snakecased_ident(&format!("{}", component_type));
     let component_name_mut = to_ident(&format!("{}_mut", component_name));
+    let visibility = &ast.vis;
 
-    quote! {
-        pub trait #component_trait {
-            fn #component_name(&self) -> &ComponentPool<#component_type>;
-            fn #component_name_mut(&mut self) -> &mut ComponentPool<#component_type>;
+    // Generic components get a generic accessor trait, which engine!() implements once per
+    // concrete instantiation listed in its components
+    let (impl_generics, type_generics, where_clause) = ast.generics.split_for_impl();
+
+    let mut component_generics = ast.generics.clone();
+    component_generics
+        .params
+        .push(syn::parse_quote!(IgnitionPools: #component_module::Pools #type_generics));
+    let (component_impl_generics, _, _) = component_generics.split_for_impl();
+
+    Ok(quote! {
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        #visibility mod #component_module {
+            #[allow(unused_imports)]
+            use super::*;
+
+            // Unsafe for the same reasons as `Component`, which is implemented on top of it
+            #[allow(clippy::missing_safety_doc)]
+            pub unsafe trait Pools #impl_generics #where_clause {
+                fn #component_name(&self) -> &::ignition::life::ComponentPool<super::#component_type #type_generics>;
+                fn #component_name_mut(&mut self) -> &mut ::ignition::life::ComponentPool<super::#component_type #type_generics>;
+            }
+        }
+
+        // The accessors only hand out the pools they were written for
+        unsafe impl #component_impl_generics ::ignition::life::Component<IgnitionPools> for #component_type #type_generics #where_clause {
+            fn get_from(component_pools: &IgnitionPools) -> &::ignition::life::ComponentPool<#component_type #type_generics> {
+                <IgnitionPools as #component_module::Pools #type_generics>::#component_name(component_pools)
+            }
+
+            fn get_mut_from(component_pools: &mut IgnitionPools) -> &mut ::ignition::life::ComponentPool<#component_type #type_generics> {
+                <IgnitionPools as #component_module::Pools #type_generics>::#component_name_mut(component_pools)
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(Bundle)]
+pub fn bundle(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as syn::DeriveInput);
+    let bundle_type = ast.ident;
+
+    if !ast.generics.params.is_empty() {
+        return syn::Error::new_spanned(
+            &ast.generics,
+            "#[derive(Bundle)] doesn't support generics",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let fields = match ast.data {
+        syn::Data::Struct(data) => data.fields,
+        syn::Data::Enum(data) => {
+            return syn::Error::new_spanned(
+                data.enum_token,
+                "#[derive(Bundle)] only supports structs",
+            )
+            .to_compile_error()
+            .into()
+        }
+        syn::Data::Union(data) => {
+            return syn::Error::new_spanned(
+                data.union_token,
+                "#[derive(Bundle)] only supports structs",
+            )
+            .to_compile_error()
+            .into()
         }
+    };
+
+    let types = fields
+        .iter()
+        .map(|x| x.ty.clone())
+        .collect::<Vec<syn::Type>>();
+
+    // "Health < u32 >" -> "Health<u32>"
+    let names = types
+        .iter()
+        .map(|x| quote!(#x).to_string().replace(' ', ""))
+        .collect::<Vec<String>>();
+
+    // Both fields would be inserted into the same pool, the second overwriting the first
+    for (i, (ty, name)) in types.iter().zip(names.iter()).enumerate() {
+        if names[..i].contains(name) {
+            return syn::Error::new_spanned(
+                ty,
+                format!("`{}` is already a field of this bundle, an entity only holds one of each component", name),
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+    let members = fields
+        .iter()
+        .enumerate()
+        .map(|(i, x)| match &x.ident {
+            Some(ident) => syn::Member::Named(ident.clone()),
+            None => syn::Member::Unnamed(syn::Index::from(i)),
+        })
+        .collect::<Vec<syn::Member>>();
+
+    quote! {
+        impl<P> ::ignition::life::flock::Bundle<P> for #bundle_type
+        where
+            P: ::ignition::life::ComponentPoolsTrait,
+            #(#types: 'static + ::ignition::life::Component<P>),*
+        {
+            fn insert_into(self, scene: &mut ::ignition::life::Scene<P>, entity: usize) {
+                #(scene.component(entity, self.#members);)*
+            }
+
+            fn missing_from(scene: &::ignition::life::Scene<P>, entity: usize) -> ::std::vec::Vec<&'static str> {
+                let mut missing = ::std::vec::Vec::new();
 
-        impl<G: #component_trait> Component<G> for #component_type {
-            fn get_from(component_pools: &G) -> &ComponentPool<#component_type> {
-                component_pools.#component_name()
+                #(if scene.get::<#types>().get(entity).is_err() {
+                    missing.push(#names);
+                })*
+
+                missing
             }
 
-            fn get_mut_from(component_pools: &mut G) -> &mut ComponentPool<#component_type> {
-                component_pools.#component_name_mut()
+            fn take_from(
+                scene: &mut ::ignition::life::Scene<P>,
+                entity: usize,
+            ) -> ::core::result::Result<Self, ::ignition::life::glitch::LifeError> {
+                ::core::result::Result::Ok(Self {
+                    #(#members: scene.take_component::<#types>(entity)?),*
+                })
             }
         }
     }
     .into()
 }
+
+fn is_out_of_registry(ast: &syn::DeriveInput) -> syn::Result<bool> {
+    let mut out_of_registry = false;
+
+    for attribute in ast.attrs.iter().filter(|x| x.path.is_ident("component")) {
+        let meta = match attribute.parse_meta()? {
+            syn::Meta::List(meta) => meta,
+            meta => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "expected #[component(no_registry)]",
+                ))
+            }
+        };
+
+        for nested in meta.nested.iter() {
+            match nested {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("no_registry") => {
+                    out_of_registry = true
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        nested,
+                        "unknown #[component] option, expected `no_registry`",
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(out_of_registry)
+}
+
+// Pools own their components for as long as the scene lives, hence the 'static
+fn check_component_shape(ast: &syn::DeriveInput) -> syn::Result<()> {
+    if let syn::Data::Union(data) = &ast.data {
+        return Err(syn::Error::new_spanned(
+            data.union_token,
+            "#[derive(Component)] only supports structs and enums",
+        ));
+    }
+
+    if let Some(lifetime) = ast.generics.lifetimes().next() {
+        return Err(syn::Error::new_spanned(
+            lifetime,
+            "components can't borrow data, lifetime parameters aren't supported",
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::expand_component;
+
+    #[test]
+    fn components_out_of_the_registry_ignore_a_hand_edited_components_toml() {
+        let dir = env::temp_dir().join(format!("ignition-component-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/lib.rs"), "").unwrap();
+        fs::write(
+            dir.join("components.toml"),
+            "[[game.0]]\nPosition = 'game::Velocity'\ncomponents_hash = 0000000000000000\n",
+        )
+        .unwrap();
+
+        env::set_var("CARGO_MANIFEST_DIR", &dir);
+        env::set_var("CARGO_CRATE_NAME", "game");
+        env::set_var("CARGO_PKG_NAME", "game");
+        env::remove_var("CARGO_BIN_NAME");
+        env::remove_var("OUT_DIR");
+
+        let listed = expand_component(&syn::parse_quote! {
+            #[component(no_registry)]
+            pub struct Position;
+        });
+        let registered = expand_component(&syn::parse_quote! {
+            pub struct Position;
+        });
+
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(listed.is_ok());
+        assert!(registered
+            .unwrap_err()
+            .to_string()
+            .contains("edited by hand"));
+    }
+
+    #[test]
+    fn unknown_options_are_reported() {
+        let error = expand_component(&syn::parse_quote! {
+            #[component(no_registery)]
+            pub struct Position;
+        })
+        .unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "unknown #[component] option, expected `no_registry`"
+        );
+    }
+}
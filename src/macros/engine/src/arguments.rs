@@ -0,0 +1,301 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, Ident, Path, Token,
+};
+use utils::accessors::{generated_components, generated_file_name};
+
+const ENGINE_KEYS: &[&str] = &["name", "renderer", "components", "crates"];
+const ECS_KEYS: &[&str] = &["components", "crates"];
+
+// Filled in by the `ignition_components!` macro of each crate listed in `crates`
+pub const IMPORTED_KEY: &str = "__imported_components";
+
+// Filled in by the `ignition_registry!` macro that utils::build::generate() writes to OUT_DIR
+pub const GENERATED_KEY: &str = "__generated_components";
+
+pub struct EngineArguments {
+    pub name: Option<Ident>,
+    pub renderer: Option<Renderer>,
+    pub components: Option<Punctuated<ComponentEntry, Token![,]>>,
+    pub crates: Option<Punctuated<Ident, Token![,]>>,
+    pub imported: Vec<Path>,
+    pub generated: bool,
+}
+
+pub struct EcsArguments(pub EngineArguments);
+
+// A component of `components`, along with the #[cfg] its pool is compiled under :
+//      components: [Position, #[cfg(feature = "editor")] Gizmo]
+pub struct ComponentEntry {
+    pub cfgs: Vec<Attribute>,
+    pub path: Path,
+}
+
+pub enum Renderer {
+    Screen,
+    Headless,
+    Custom(Path),
+}
+
+impl Parse for EngineArguments {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        parse_arguments(input, "engine", ENGINE_KEYS)
+    }
+}
+
+impl Parse for EcsArguments {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        parse_arguments(input, "ecs", ECS_KEYS).map(EcsArguments)
+    }
+}
+
+fn parse_arguments(
+    input: ParseStream,
+    macro_name: &str,
+    keys: &[&str],
+) -> syn::Result<EngineArguments> {
+    let mut arguments = EngineArguments {
+        name: None,
+        renderer: None,
+        components: None,
+        crates: None,
+        imported: Vec::new(),
+        generated: false,
+    };
+
+    while !input.is_empty() {
+        let key: Ident = input.parse()?;
+
+        let hidden = [IMPORTED_KEY, GENERATED_KEY].contains(&key.to_string().as_str());
+
+        if !keys.contains(&key.to_string().as_str()) && !hidden {
+            return Err(syn::Error::new(
+                key.span(),
+                format!(
+                    "unknown {}!() parameter `{}`, expected one of : {}",
+                    macro_name,
+                    key,
+                    keys.join(", ")
+                ),
+            ));
+        }
+
+        if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+        } else {
+            input.parse::<Token![=]>()?;
+        }
+
+        match key.to_string().as_str() {
+            "name" => set_once(&mut arguments.name, &key, input.parse()?)?,
+            "renderer" => set_once(&mut arguments.renderer, &key, input.parse()?)?,
+            "crates" => {
+                let content;
+                bracketed!(content in input);
+
+                let crates = content.parse_terminated(Ident::parse)?;
+                set_once(&mut arguments.crates, &key, crates)?;
+            }
+            "components" | GENERATED_KEY => {
+                arguments.generated = key == GENERATED_KEY;
+
+                let content;
+                bracketed!(content in input);
+
+                let components = content.parse_terminated(ComponentEntry::parse)?;
+                set_once(&mut arguments.components, &key, components)?;
+            }
+            _ => {
+                let content;
+                bracketed!(content in input);
+
+                let imported = content.parse_terminated::<Path, Token![,]>(Path::parse)?;
+                arguments.imported.extend(imported);
+            }
+        }
+
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+        }
+    }
+
+    Ok(arguments)
+}
+
+impl Parse for ComponentEntry {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let cfgs = Attribute::parse_outer(input)?;
+
+        if let Some(attribute) = cfgs.iter().find(|x| !x.path.is_ident("cfg")) {
+            return Err(syn::Error::new_spanned(
+                attribute,
+                "only #[cfg] attributes can be put on components",
+            ));
+        }
+
+        Ok(ComponentEntry {
+            cfgs,
+            path: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for ComponentEntry {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        let cfgs = &self.cfgs;
+        let path = &self.path;
+
+        quote! { #(#cfgs)* #path }.to_tokens(tokens)
+    }
+}
+
+impl Parse for Renderer {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path: Path = input.parse()?;
+
+        Ok(match path.get_ident().map(|x| x.to_string()).as_deref() {
+            Some("Screen") => Renderer::Screen,
+            Some("Headless") => Renderer::Headless,
+            _ => Renderer::Custom(path),
+        })
+    }
+}
+
+impl ToTokens for Renderer {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        match self {
+            Renderer::Screen => quote! { Screen }.to_tokens(tokens),
+            Renderer::Headless => quote! { Headless }.to_tokens(tokens),
+            Renderer::Custom(path) => path.to_tokens(tokens),
+        }
+    }
+}
+
+impl EngineArguments {
+    // Hands the arguments over to the `ignition_components!` macro of the next crate to import,
+    // which calls `macro_name` back with its own components appended
+    pub fn forward_to_next_crate(&mut self, macro_name: &str) -> Option<TokenStream2> {
+        let mut crates = self.crates.take()?.into_iter().collect::<Vec<Ident>>();
+
+        if crates.is_empty() {
+            return None;
+        }
+
+        let next_crate = crates.remove(0);
+        let macro_name = Ident::new(macro_name, next_crate.span());
+        let imported_key = Ident::new(IMPORTED_KEY, next_crate.span());
+
+        let name = self.name.iter();
+        let renderer = self.renderer.iter();
+        let components = self.components.iter();
+        let imported = &self.imported;
+
+        Some(quote! {
+            ::#next_crate::ignition_components! {
+                ::ignition::prelude::#macro_name;
+                #(name = #name,)*
+                #(renderer = #renderer,)*
+                #(components: [#components],)*
+                crates: [#(#crates),*],
+                #imported_key: [#(#imported),*],
+            }
+        })
+    }
+
+    // Hands the arguments over to the `ignition_registry!` macro generated by the build script,
+    // which calls `macro_name` back with the components of the crate. Including it lets rustc
+    // know the expansion depends on it
+    pub fn forward_to_generated_registry(&self, macro_name: &str) -> Option<TokenStream2> {
+        if self.components.is_some() || self.crates.is_some() {
+            return None;
+        }
+
+        generated_components()?;
+
+        let file_name = format!("/{}", generated_file_name());
+        let macro_name = Ident::new(macro_name, proc_macro2::Span::call_site());
+        let imported_key = Ident::new(IMPORTED_KEY, proc_macro2::Span::call_site());
+
+        let name = self.name.iter();
+        let renderer = self.renderer.iter();
+        let imported = &self.imported;
+
+        Some(quote! {
+            include!(concat!(env!("OUT_DIR"), #file_name));
+
+            ignition_registry! {
+                ::ignition::prelude::#macro_name;
+                #(name = #name,)*
+                #(renderer = #renderer,)*
+                #imported_key: [#(#imported),*],
+            }
+        })
+    }
+}
+
+fn set_once<T>(argument: &mut Option<T>, key: &Ident, value: T) -> syn::Result<()> {
+    if argument.is_some() {
+        return Err(syn::Error::new(
+            key.span(),
+            format!("parameter `{}` is specified more than once", key),
+        ));
+    }
+
+    *argument = Some(value);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EcsArguments, EngineArguments, Renderer};
+
+    fn error<T: syn::parse::Parse>(input: &str) -> String {
+        match syn::parse_str::<T>(input) {
+            Ok(_) => panic!("`{}` should not parse", input),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn every_parameter_is_parsed() {
+        let arguments = syn::parse_str::<EngineArguments>(
+            "name = Sim, renderer = Headless, components: [Position, #[cfg(test)] Velocity]",
+        )
+        .unwrap();
+
+        assert_eq!(arguments.name.unwrap(), "Sim");
+        assert!(matches!(arguments.renderer, Some(Renderer::Headless)));
+        assert_eq!(arguments.components.unwrap().len(), 2);
+
+        let arguments = syn::parse_str::<EngineArguments>("renderer = my::Renderer").unwrap();
+        assert!(matches!(arguments.renderer, Some(Renderer::Custom(_))));
+    }
+
+    #[test]
+    fn unknown_parameters_are_reported() {
+        assert_eq!(
+            error::<EngineArguments>("title = Game"),
+            "unknown engine!() parameter `title`, expected one of : name, renderer, components, crates"
+        );
+        assert_eq!(
+            error::<EcsArguments>("name = Sim"),
+            "unknown ecs!() parameter `name`, expected one of : components, crates"
+        );
+    }
+
+    #[test]
+    fn repeated_parameters_are_reported() {
+        assert_eq!(
+            error::<EngineArguments>("name = Sim, renderer = Headless, name = Game"),
+            "parameter `name` is specified more than once"
+        );
+        assert_eq!(
+            error::<EngineArguments>("components: [Position], components: [Velocity]"),
+            "parameter `components` is specified more than once"
+        );
+    }
+}
@@ -1,65 +1,424 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use std::collections::HashMap;
 
-use regex::Regex;
-use syn::{Ident, ItemUse};
+use syn::{
+    parse_macro_input, punctuated::Punctuated, spanned::Spanned, Ident, Path, PathSegment, Token,
+};
 
-use utils::{accessors::get_current_crate, parsing::*, update_components};
+use utils::{
+    accessors::get_current_crate, get_verified_components, parsing::*,
+    search_logic::find_engine_invocations,
+};
+
+mod arguments;
+
+use arguments::{ComponentEntry, EcsArguments, EngineArguments, Renderer};
+
+#[proc_macro]
+pub fn engine(input: TokenStream) -> TokenStream {
+    let mut arguments = parse_macro_input!(input as EngineArguments);
+
+    if let Some(forwarded) = arguments.forward_to_next_crate("engine") {
+        return forwarded.into();
+    }
+
+    if let Some(forwarded) = arguments.forward_to_generated_registry("engine") {
+        return forwarded.into();
+    }
+
+    // The build script already checked it when it generated the components
+    let engines = match arguments.generated {
+        true => Vec::new(),
+        false => find_engine_invocations(),
+    };
+    if engines.len() > 1 {
+        return to_compile_error(format!(
+            "engine!() can only be invoked once per crate, but it is invoked in each of : {}",
+            engines.join(", ")
+        ))
+        .into();
+    }
+
+    let ecs = generate_ecs(arguments.components, arguments.imported);
+    let engine = generate_engine(
+        arguments.name.unwrap_or(to_ident(&String::from("Engine"))),
+        arguments.renderer.unwrap_or(Renderer::Screen),
+    );
+
+    quote! {
+        #ecs
+        #engine
+    }
+    .into()
+}
+
+#[proc_macro]
+pub fn ecs(input: TokenStream) -> TokenStream {
+    let EcsArguments(mut arguments) = parse_macro_input!(input as EcsArguments);
+
+    if let Some(forwarded) = arguments.forward_to_next_crate("ecs") {
+        return forwarded.into();
+    }
+
+    if let Some(forwarded) = arguments.forward_to_generated_registry("ecs") {
+        return forwarded.into();
+    }
+
+    generate_ecs(arguments.components, arguments.imported).into()
+}
 
 #[proc_macro]
-pub fn engine(_input: TokenStream) -> TokenStream {
-    let components = update_components().unwrap_or(parse_components());
+pub fn export_components(input: TokenStream) -> TokenStream {
+    let EcsArguments(arguments) = parse_macro_input!(input as EcsArguments);
+
+    if let Some(forwarded) = arguments.forward_to_generated_registry("export_components") {
+        return forwarded.into();
+    }
+
+    let entries = match arguments.components {
+        Some(components) => components
+            .into_iter()
+            .map(|x| ComponentEntry {
+                cfgs: x.cfgs,
+                path: strip_crate(x.path),
+            })
+            .collect(),
+        None => {
+            let components = match get_verified_components() {
+                Ok(components) => components,
+                Err(message) => return to_compile_error(message).into(),
+            };
+
+            let entries = get_components_of_current_crate(&components)
+                .iter()
+                .map(|x| parse_registered::<ComponentEntry>(x))
+                .collect::<syn::Result<Vec<ComponentEntry>>>();
+
+            match entries {
+                Ok(entries) => entries,
+                Err(error) => return error.to_compile_error().into(),
+            }
+        }
+    };
+
+    generate_export(&entries).into()
+}
+
+// The #[cfg]s of the components have to hold in the crate exporting them, not in the one
+// importing them. So `ignition_components!` walks a chain of macros, one per component, each
+// defined under its #[cfg] to append it and under the opposite one to skip it
+fn generate_export(entries: &[ComponentEntry]) -> TokenStream2 {
+    let steps = (0..=entries.len())
+        .map(|i| to_ident(&format!("__ignition_components_{}", i)))
+        .collect::<Vec<Ident>>();
+    let first = &steps[0];
+
+    let mut chain = Vec::new();
+    for (entry, step) in entries.iter().zip(steps.windows(2)) {
+        let (current, next) = (&step[0], &step[1]);
+        let path = &entry.path;
+
+        let append = quote! {
+            #[doc(hidden)]
+            #[macro_export]
+            macro_rules! #current {
+                ($callback:tt [$($components:tt)*] $($arguments:tt)*) => {
+                    $crate::#next! { $callback [$($components)* $crate::#path,] $($arguments)* }
+                };
+            }
+        };
+
+        if entry.cfgs.is_empty() {
+            chain.push(append);
+            continue;
+        }
+
+        let predicates = match entry
+            .cfgs
+            .iter()
+            .map(|x| syn::parse2::<proc_macro2::Group>(x.tokens.clone()).map(|x| x.stream()))
+            .collect::<syn::Result<Vec<TokenStream2>>>()
+        {
+            Ok(predicates) => predicates,
+            Err(error) => return error.to_compile_error(),
+        };
+
+        chain.push(quote! {
+            #[cfg(all(#(#predicates),*))]
+            #append
+
+            #[cfg(not(all(#(#predicates),*)))]
+            #[doc(hidden)]
+            #[macro_export]
+            macro_rules! #current {
+                ($callback:tt [$($components:tt)*] $($arguments:tt)*) => {
+                    $crate::#next! { $callback [$($components)*] $($arguments)* }
+                };
+            }
+        });
+    }
+
+    let last = &steps[entries.len()];
+
+    quote! {
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! ignition_components {
+            (:: $($callback:ident)::+ ; $($arguments:tt)*) => {
+                $crate::#first! { [:: $($callback)::+] [] $($arguments)* }
+            };
+        }
+
+        #(#chain)*
+
+        #[doc(hidden)]
+        #[macro_export]
+        macro_rules! #last {
+            ([:: $($callback:ident)::+] [$($components:tt)*] $($arguments:tt)*) => {
+                :: $($callback)::+ ! {
+                    $($arguments)*
+                    __imported_components: [$($components)*],
+                }
+            };
+        }
+    }
+}
+
+fn strip_crate(mut path: Path) -> Path {
+    if path.segments.first().is_some_and(|x| x.ident == "crate") {
+        path.segments = path.segments.into_iter().skip(1).collect();
+    }
+
+    path.leading_colon = None;
+    path
+}
+
+fn generate_ecs(
+    components: Option<Punctuated<ComponentEntry, Token![,]>>,
+    imported: Vec<Path>,
+) -> TokenStream2 {
+    let (entries, mut descriptions) = match components {
+        Some(components) => {
+            let entries = components.into_iter().collect::<Vec<ComponentEntry>>();
+            let descriptions = entries
+                .iter()
+                .map(|x| path_to_string(&x.path))
+                .collect::<Vec<String>>();
+
+            (entries, descriptions)
+        }
+        None => {
+            let components = match get_verified_components() {
+                Ok(components) => components,
+                Err(message) => return to_compile_error(message),
+            };
+
+            // "ignition::life::Name #[cfg(test)]" -> "ignition::life::Name"
+            let descriptions = components
+                .iter()
+                .map(|(_x, y)| split_cfgs(y).0.to_string())
+                .collect::<Vec<String>>();
+
+            // Referred to by their full path rather than imported, so that components sharing
+            // a name don't clash : "#[cfg(test)] crate::life::Name"
+            let entries = components
+                .iter()
+                .zip(descriptions.iter())
+                .map(|((_x, y), path)| {
+                    let (_path, cfgs) = split_cfgs(y);
+                    parse_registered::<ComponentEntry>(&format!("{} {}", cfgs, to_crate_path(path)))
+                })
+                .collect::<syn::Result<Vec<ComponentEntry>>>();
+
+            match entries {
+                Ok(entries) => (entries, descriptions),
+                Err(error) => return error.to_compile_error(),
+            }
+        }
+    };
+
+    let mut components = Vec::new();
+    let mut cfgs = Vec::new();
+
+    for entry in entries {
+        let attributes = entry.cfgs;
+
+        components.push(entry.path);
+        cfgs.push(quote! { #(#attributes)* });
+    }
+
+    descriptions.extend(imported.iter().map(path_to_string));
+    for path in imported {
+        components.push(path);
+        cfgs.push(quote! {});
+    }
+
+    let labels = components.iter().map(last_segment).collect::<Vec<String>>();
+    let types_trait = components.iter().map(to_trait_path).collect::<Vec<Path>>();
 
-    let types = convert_type_names(&components, |x| to_ident(x));
-    let types_trait = convert_type_names(&components, |x| to_ident(&format!("{}Trait", x)));
-    let names = convert_type_names(&components, |x| to_snakecased_ident(&x));
-    let names_mut = convert_type_names(&components, |x| to_snakecased_ident(&format!("{}_mut", x)));
+    // Fields are named after the module and the instantiation of each component (`physics_position`,
+    // `ui_position`, `health_f32`), accessors after the names declared by its derive (`position`)
+    let fields = components.iter().map(to_field_name).collect::<Vec<Ident>>();
+    let names = convert_paths(&components, |x| to_snakecased_ident(&x.to_string()));
+    let names_mut = convert_paths(&components, |x| to_snakecased_ident(&format!("{}_mut", x)));
 
-    let paths = finish_formatting_paths(&components);
+    if let Err(error) = check_fields_are_unique(&fields, &components, &descriptions) {
+        return error.to_compile_error();
+    }
+
+    let types = components;
+
+    // `Scene::dump()` prints every component, which is otherwise reported far from the component
+    // that doesn't implement Debug
+    let debug_checks = types.iter().zip(cfgs.iter()).map(|(x, cfgs)| {
+        quote_spanned! { x.span() =>
+            #cfgs const _: () = {
+                const fn components_must_implement_debug<T: ::core::fmt::Debug>() {}
+                components_must_implement_debug::<#x>();
+            };
+        }
+    });
 
     quote! {
-        #(#paths)*
+        #(#debug_checks)*
 
         pub struct ComponentPools {
-            #(pub #names : ComponentPool<#types>),*
+            #(#cfgs pub #fields : ::ignition::life::ComponentPool<#types>),*
+        }
+
+        // Every pool borrowed at once, without going through the scene :
+        //      let ComponentPoolsMut { position, velocity, .. } = scene.component_pools.split();
+        pub struct ComponentPoolsMut<'a> {
+            #(#cfgs pub #fields : &'a mut ::ignition::life::ComponentPool<#types>,)*
+            _pools: ::core::marker::PhantomData<&'a mut ComponentPools>,
+        }
+
+        impl ComponentPools {
+            pub fn split(&mut self) -> ComponentPoolsMut<'_> {
+                ComponentPoolsMut {
+                    #(#cfgs #fields : &mut self.#fields,)*
+                    _pools: ::core::marker::PhantomData,
+                }
+            }
         }
 
-        impl ComponentPoolsTrait for ComponentPools {
+        impl ::ignition::life::ComponentPoolsTrait for ComponentPools {
             fn new() -> Self {
                 Self {
-                    #(#names : ComponentPool::empty()),*
+                    #(#cfgs #fields : ::ignition::life::ComponentPool::empty()),*
                 }
             }
 
-            fn delete_entity(&mut self, entity: usize) {
-                #(self.#names.delete_entity(entity);)*
+            fn delete_entity(
+                &mut self,
+                entity: usize,
+            ) -> ::core::result::Result<(), ::ignition::life::glitch::LifeError> {
+                let result = ::core::result::Result::Ok(());
+                #(#cfgs let result = result.and(
+                    ::ignition::life::annihilation::EntityDestructor::try_delete_entity(&mut self.#fields, entity),
+                );)*
+
+                result
+            }
+
+            fn disable_entity(
+                &mut self,
+                entity: usize,
+                disabled: &mut ::std::vec::Vec<::core::any::TypeId>,
+            ) -> ::core::result::Result<(), ::ignition::life::glitch::LifeError> {
+                let result = ::core::result::Result::Ok(());
+                #(#cfgs let result = match ::ignition::life::ghost::ComponentToggler::try_disable_if_enabled(&mut self.#fields, entity) {
+                    ::core::result::Result::Ok(true) => {
+                        disabled.push(::core::any::TypeId::of::<#types>());
+                        result
+                    }
+                    ::core::result::Result::Ok(false) => result,
+                    ::core::result::Result::Err(error) => result.and(::core::result::Result::Err(error)),
+                };)*
+
+                result
+            }
+
+            fn enable_entity(
+                &mut self,
+                entity: usize,
+                disabled: &[::core::any::TypeId],
+            ) -> ::core::result::Result<(), ::ignition::life::glitch::LifeError> {
+                let result = ::core::result::Result::Ok(());
+                #(#cfgs let result = match disabled.contains(&::core::any::TypeId::of::<#types>()) {
+                    true => result.and(
+                        ::ignition::life::ghost::ComponentToggler::try_enable_if_disabled(&mut self.#fields, entity).map(|_| ()),
+                    ),
+                    false => result,
+                };)*
+
+                result
+            }
+
+            fn visit_pools<V: ::ignition::life::census::PoolVisitor>(&self, visitor: &mut V) {
+                #(#cfgs ::ignition::life::census::PoolVisitor::visit(
+                    visitor,
+                    #labels,
+                    &self.#fields,
+                );)*
             }
         }
 
-        #(impl #types_trait for ComponentPools {
-            fn #names(&self) -> &ComponentPool<#types> {
-                &self.#names
+        // Each pool is a field of its own
+        #(#cfgs unsafe impl #types_trait for ComponentPools {
+            fn #names(&self) -> &::ignition::life::ComponentPool<#types> {
+                &self.#fields
             }
 
-            fn #names_mut(&mut self) -> &mut ComponentPool<#types> {
-                &mut self.#names
+            fn #names_mut(&mut self) -> &mut ::ignition::life::ComponentPool<#types> {
+                &mut self.#fields
             }
         })*
+    }
+}
 
-        pub struct Engine {
-            pub renderer: Screen,
-            pub scene: Scene<ComponentPools>,
+fn generate_engine(name: Ident, renderer: Renderer) -> TokenStream2 {
+    let (renderer_type, constructor) = match renderer {
+        Renderer::Screen => (
+            quote! { ::ignition::manifestation::Screen },
+            quote! {
+                let renderer = ::ignition::manifestation::Screen::new();
+                let config = ::ignition::liberty::RuntimeConfiguration {
+                    size: renderer.window.inner_size(),
+                    ..::ignition::liberty::RuntimeConfiguration::default()
+                };
+            },
+        ),
+        Renderer::Custom(path) => (
+            quote! { #path },
+            quote! {
+                let renderer = #path::new();
+                let config = ::ignition::liberty::RuntimeConfiguration::default();
+            },
+        ),
+        Renderer::Headless => (
+            quote! { ::ignition::manifestation::Headless },
+            quote! {
+                let renderer = ::ignition::manifestation::Headless;
+                let config = ::ignition::liberty::RuntimeConfiguration::default();
+            },
+        ),
+    };
 
-            pub config: RuntimeConfiguration,
+    quote! {
+        pub struct #name {
+            pub renderer: #renderer_type,
+            pub scene: ::ignition::life::Scene<ComponentPools>,
+
+            pub config: ::ignition::liberty::RuntimeConfiguration,
         }
 
-        impl Engine {
+        impl #name {
             pub fn ignite() -> Self {
-                let renderer = Screen::new();
-                let scene = Scene::new();
-
-                let mut config = RuntimeConfiguration::default();
-                config.size = renderer.window.inner_size();
+                #constructor
+                let scene = ::ignition::life::Scene::new();
 
                 Self {
                     renderer,
@@ -69,29 +428,145 @@ pub fn engine(_input: TokenStream) -> TokenStream {
                 }
             }
         }
+
+        impl ::ignition::cockpit::IgnitionEngine for #name {
+            type Pools = ComponentPools;
+            type Renderer = #renderer_type;
+
+            fn scene(&self) -> &::ignition::life::Scene<ComponentPools> {
+                &self.scene
+            }
+
+            fn scene_mut(&mut self) -> &mut ::ignition::life::Scene<ComponentPools> {
+                &mut self.scene
+            }
+
+            fn renderer(&self) -> &#renderer_type {
+                &self.renderer
+            }
+
+            fn renderer_mut(&mut self) -> &mut #renderer_type {
+                &mut self.renderer
+            }
+
+            fn config(&self) -> &::ignition::liberty::RuntimeConfiguration {
+                &self.config
+            }
+
+            fn config_mut(&mut self) -> &mut ::ignition::liberty::RuntimeConfiguration {
+                &mut self.config
+            }
+        }
     }
-    .into()
 }
 
-fn convert_type_names(
-    components: &Vec<(String, String)>,
-    closure: impl Fn(&String) -> Ident,
-) -> Vec<Ident> {
-    components.iter().map(|(x, _y)| closure(x)).collect()
+fn to_compile_error(message: String) -> TokenStream2 {
+    syn::Error::new(proc_macro2::Span::call_site(), message).to_compile_error()
 }
 
-fn finish_formatting_paths(components: &Vec<(String, String)>) -> Vec<ItemUse> {
-    let engine_path = get_path_of_engine_in_current_crate();
-    let regex = Regex::new(&format!(r"{}::\{{.*\}}", engine_path)).unwrap();
+// Two components snake-casing to the same name would otherwise surface as a duplicate field
+fn check_fields_are_unique(
+    fields: &[Ident],
+    components: &[Path],
+    descriptions: &[String],
+) -> syn::Result<()> {
+    let mut owners = HashMap::new();
+    let mut errors: Option<syn::Error> = None;
 
-    components
-        .iter()
-        .filter(|(_x, y)| !regex.is_match(y))
-        .map(|(_x, y)| format_path(y))
-        .collect()
+    for (i, field) in fields.iter().enumerate() {
+        if let Some(owner) = owners.insert(field.to_string(), i) {
+            let error = syn::Error::new_spanned(
+                &components[i],
+                format!(
+                    "`{}` and `{}` would both be stored in the pool `{}`, rename one of them",
+                    descriptions[owner], descriptions[i], field
+                ),
+            );
+
+            match errors.as_mut() {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            }
+        }
+    }
+
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(()),
+    }
+}
+
+// What the registry holds is only checked for tampering, not for being valid Rust
+fn parse_registered<T: syn::parse::Parse>(string: &str) -> syn::Result<T> {
+    syn::parse_str::<T>(string).map_err(|_| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!(
+                "components.toml lists `{}` which isn't a valid path, run `ignition-registry regenerate`",
+                string
+            ),
+        )
+    })
+}
+
+fn path_to_string(path: &Path) -> String {
+    quote!(#path).to_string().replace(' ', "")
+}
+
+fn convert_paths<T>(components: &[Path], closure: impl Fn(&Ident) -> T) -> Vec<T> {
+    components.iter().map(|x| closure(&last_ident(x))).collect()
 }
 
-fn format_path(path: &String) -> ItemUse {
-    let path = path.replace(&get_current_crate(), "crate");
-    syn::parse_str::<ItemUse>(&format!("use {};", path)).unwrap()
+fn last_ident(path: &Path) -> Ident {
+    path.segments.last().unwrap().ident.clone()
+}
+
+fn last_segment(path: &Path) -> String {
+    let last = path.segments.last().unwrap();
+
+    quote!(#last).to_string().replace(' ', "") // "Health<f32>"
+}
+
+// "physics::Health<f32>" -> "physics::__ignition_Health::Pools<f32>"
+fn to_trait_path(path: &Path) -> Path {
+    let mut path = path.clone();
+    let last = path.segments.pop().unwrap().into_value();
+
+    path.segments
+        .push(PathSegment::from(to_accessor_module(&last.ident)));
+    path.segments.push(PathSegment {
+        ident: to_ident(&String::from("Pools")),
+        arguments: last.arguments,
+    });
+
+    path
+}
+
+// "ignition::life::Name" -> "crate::life::Name"
+fn to_crate_path(path: &str) -> String {
+    let current_crate = get_current_crate();
+
+    match path.strip_prefix(&current_crate) {
+        Some(rest) if rest.starts_with("::") => format!("crate{}", rest),
+        _ => path.to_string(),
+    }
+}
+
+// "crate::physics::Position" -> "physics_position", "$crate::Health<f32>" -> "health_f32"
+fn to_field_name(path: &Path) -> Ident {
+    let modules = path
+        .segments
+        .iter()
+        .take(path.segments.len() - 1)
+        .map(|x| x.ident.to_string())
+        .filter(|x| !["crate", "$crate", "self", "super"].contains(&x.as_str()))
+        .collect::<Vec<String>>();
+
+    let mut name = modules.join("_");
+    if !name.is_empty() {
+        name.push('_');
+    }
+    name.push_str(&last_segment(path));
+
+    to_snakecased_ident(&name)
 }
@@ -1,72 +1,109 @@
-use std::{env, fs, fs::OpenOptions, path::PathBuf, time::SystemTime};
+use std::{env, fs, path::PathBuf, time::SystemTime};
 
 use regex::Regex;
 
-use crate::search_logic::add_component_to_module_path;
+use crate::integrity::fnv1a;
 
 /* FILES */
 
-pub fn tempfile() -> PathBuf {
-    let mut tempfile = env::temp_dir();
-    tempfile.push("components.toml");
+// Shared by every target of a package, each of them filing its components in its own section
+pub fn components_toml() -> PathBuf {
+    registry_dir().join("components.toml")
+}
 
-    tempfile
+pub fn components_lock() -> PathBuf {
+    registry_dir().join("components.lock")
 }
 
-pub fn components_toml() -> fs::File {
-    fs::OpenOptions::new()
-        .write(true)
-        .create(true)
-        .open("components.toml")
-        .unwrap()
+// Written by build::generate(), relative to OUT_DIR so that engine!() can include!() it
+pub fn generated_file_name() -> String {
+    format!("ignition/{}.rs", get_section_name())
+}
+
+// Only crates whose build script calls build::generate() have one
+pub fn generated_components() -> Option<PathBuf> {
+    let path = PathBuf::from(env::var_os("OUT_DIR")?).join(generated_file_name());
+
+    match path.is_file() {
+        true => Some(path),
+        false => None,
+    }
+}
+
+// One per package, two checkouts of the same crate must not overwrite each other's components
+pub fn tempfile() -> PathBuf {
+    let package = fnv1a(manifest_dir().to_string_lossy().as_bytes());
+
+    env::temp_dir().join(format!("ignition-components-{:016x}.toml", package))
 }
 
 pub fn get_components() -> String {
-    match fs::read_to_string("components.toml") {
+    match fs::read_to_string(components_toml()) {
         Ok(ids) => ids,
-        Err(_) => match fs::read_to_string(tempfile().as_path()) {
-            Ok(ids) => ids,
-            Err(_) => String::new(),
-        },
+        Err(_) => fs::read_to_string(tempfile().as_path()).unwrap_or_default(),
     }
 }
 
+// Everything listed under the header of the target being compiled, header excluded
+pub fn get_current_section() -> Option<String> {
+    section_regex()
+        .captures(&get_components())
+        .map(|cap| cap[2].to_string())
+}
+
+// Captures the time of the last update, then the lines of the section
+pub fn section_regex() -> Regex {
+    let section = regex::escape(&get_section_name());
+    Regex::new(&format!(r"\[\[{}\.(\d*)\]\]\n((?:.* = .*\n)*)", section)).unwrap()
+}
+
 /* PATHS */
 
-pub fn source_dir() -> PathBuf {
-    let mut source_directory = PathBuf::new();
-    source_directory.push(".");
-    source_directory.push("src");
+// Cargo runs rustc from the workspace root, so the current directory only works for packages
+// that aren't part of a workspace
+pub fn manifest_dir() -> PathBuf {
+    match env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => env::current_dir().unwrap(),
+    }
+}
 
-    source_directory
+// Crates with a build script get their components filed next to its output, like any other
+// generated file
+pub fn registry_dir() -> PathBuf {
+    match env::var_os("OUT_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => manifest_dir(),
+    }
 }
 
-pub fn get_current_crate() -> String {
-    env::current_dir()
-        .unwrap() // PathBuf { "/home/user/Projects/ignition/" }
-        .components() // Iterator: home -> user -> Projects -> ignition
-        .last() // Some("ignition")
-        .unwrap() // "ignition"
-        .as_os_str() // Endless conversions...
-        .to_str()
-        .unwrap()
-        .to_string()
+pub fn source_dir() -> PathBuf {
+    manifest_dir().join("src")
 }
 
-pub fn get_component_module_path(path: &PathBuf, name: &String) -> String {
-    add_component_to_module_path(get_module_path(path), name)
+pub fn get_current_crate() -> String {
+    if let Ok(name) = env::var("CARGO_CRATE_NAME") {
+        return name;
+    }
+
+    let package = match env::var("CARGO_PKG_NAME") {
+        Ok(name) => name,
+        Err(_) => manifest_dir()
+            .file_name() // "/home/user/Projects/ignition/" -> "ignition"
+            .unwrap()
+            .to_string_lossy()
+            .to_string(),
+    };
+
+    package.replace('-', "_") // "ignition-core" -> "ignition_core"
 }
 
-pub fn get_module_path(path: &PathBuf) -> String {
-    path // ./src/life/genesis.rs
-        .components() // Iterator: . -> src -> life -> genesis.rs
-        .map(|x| x.as_os_str().to_str().unwrap()) // Conversion to &str
-        .collect::<Vec<&str>>() // vec!(".", "src", "life", "genesis.rs")
-        .join("::") // ".::src::life::genesis.rs"
-        .replace(".::src", &format!("{}", &get_current_crate())) // "ignition::life::genesis.rs"
-        .replace(".rs", "") // "ignition::life::genesis"
-        .replace("::lib", "") // if "use ignition::lib" then "use ignition"
-        .replace("::main", "") // if "use application::main" then "use application"
+// The library and the binary of a package usually share the crate name, yet not their components
+pub fn get_section_name() -> String {
+    match env::var("CARGO_BIN_NAME") {
+        Ok(_) => format!("{}.bin", get_current_crate()),
+        Err(_) => get_current_crate(),
+    }
 }
 
 /* INFORMATION */
@@ -79,20 +116,10 @@ pub fn get_current_time() -> u64 {
 }
 
 pub fn get_time_since_last_update() -> u64 {
-    let regex = Regex::new(&format!(r"\[\[{}.(\d*)\]\]", get_current_crate())).unwrap();
-
     let mut time_of_previous_sync = 0;
-    if let Some(cap) = regex.captures(&get_components()) {
-        time_of_previous_sync = cap[1].parse::<u64>().unwrap();
+    if let Some(cap) = section_regex().captures(&get_components()) {
+        time_of_previous_sync = cap[1].parse::<u64>().unwrap_or(0);
     }
 
     time_of_previous_sync
 }
-
-pub fn components_are_locked() -> bool {
-    OpenOptions::new()
-        .write(true)
-        .create_new(true)
-        .open("components.lock")
-        .is_err()
-}
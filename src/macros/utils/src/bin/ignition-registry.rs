@@ -0,0 +1,284 @@
+use std::{env, fs, path::PathBuf, process::exit, time::Duration};
+
+use regex::Regex;
+
+use utils::{
+    accessors::{components_lock, components_toml, get_section_name},
+    integrity::verify_components,
+    lock::RegistryLock,
+    parsing::split_cfgs,
+    regenerate_components,
+    search_logic::discover,
+};
+
+const USAGE: &str = "Usage: ignition-registry <COMMAND> [OPTIONS]
+
+Commands:
+    list          List the components of the crate and their module paths
+    check         Exit with an error if components.toml doesn't match the sources
+    regenerate    Rewrite the section of the crate in components.toml
+    unlock        Remove components.lock, unless another process holds it
+
+Options:
+    --manifest-path <PATH>    Path to Cargo.toml [default: ./Cargo.toml]
+    --bin <NAME>              Work on a binary instead of the library
+    --test <NAME>             Work on an integration test, bench or example instead of the library
+    --out-dir <DIR>           OUT_DIR of the crate, if it has a build script
+    --force                   Remove components.lock even if it is held";
+
+struct Options {
+    command: String,
+    manifest_path: PathBuf,
+    bin: Option<String>,
+    test: Option<String>,
+    out_dir: Option<PathBuf>,
+    force: bool,
+}
+
+fn main() {
+    let options = match parse_options(env::args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            exit(2);
+        }
+    };
+
+    if let Err(message) = impersonate_cargo(&options) {
+        eprintln!("error: {}", message);
+        exit(2);
+    }
+
+    let result = match options.command.as_str() {
+        "list" => list(),
+        "check" => check(),
+        "regenerate" => regenerate(),
+        _ => unlock(options.force),
+    };
+
+    if let Err(message) = result {
+        eprintln!("error: {}", message);
+        exit(1);
+    }
+}
+
+fn parse_options(arguments: Vec<String>) -> Result<Options, String> {
+    let mut arguments = arguments.into_iter();
+
+    let command = match arguments.next() {
+        Some(command) if ["list", "check", "regenerate", "unlock"].contains(&command.as_str()) => {
+            command
+        }
+        Some(command) => return Err(format!("unknown command `{}`", command)),
+        None => return Err(String::from("no command given")),
+    };
+
+    let mut options = Options {
+        command,
+        manifest_path: PathBuf::from("Cargo.toml"),
+        bin: None,
+        test: None,
+        out_dir: None,
+        force: false,
+    };
+
+    while let Some(argument) = arguments.next() {
+        let mut value = || {
+            arguments
+                .next()
+                .ok_or(format!("`{}` expects a value", argument))
+        };
+
+        match argument.as_str() {
+            "--manifest-path" => options.manifest_path = PathBuf::from(value()?),
+            "--bin" => options.bin = Some(value()?),
+            "--test" => options.test = Some(value()?),
+            "--out-dir" => options.out_dir = Some(PathBuf::from(value()?)),
+            "--force" => options.force = true,
+            _ => return Err(format!("unknown option `{}`", argument)),
+        }
+    }
+
+    Ok(options)
+}
+
+// The registry finds the crate through the environment Cargo gives to rustc, so we give it the
+// same one
+fn impersonate_cargo(options: &Options) -> Result<(), String> {
+    let manifest = fs::read_to_string(&options.manifest_path).map_err(|err| {
+        format!(
+            "Unable to read {} : {}",
+            options.manifest_path.display(),
+            err
+        )
+    })?;
+
+    // The [package] table ends where the next table starts
+    let table = manifest
+        .split("\n[")
+        .find(|x| x.trim_start_matches('[').starts_with("package]"))
+        .unwrap_or_default();
+
+    let regex = Regex::new(r#"(?m)^\s*name\s*=\s*"([^"]+)""#).unwrap();
+    let package = match regex.captures(table) {
+        Some(cap) => cap[1].to_string(),
+        None => {
+            return Err(format!(
+                "{} has no [package] name",
+                options.manifest_path.display()
+            ))
+        }
+    };
+
+    let manifest_dir = fs::canonicalize(&options.manifest_path)
+        .map_err(|err| format!("{} : {}", options.manifest_path.display(), err))?
+        .parent()
+        .unwrap()
+        .to_path_buf();
+
+    let crate_name = match (&options.bin, &options.test) {
+        (Some(name), _) | (_, Some(name)) => name,
+        _ => &package,
+    };
+
+    env::set_var("CARGO_MANIFEST_DIR", manifest_dir);
+    env::set_var("CARGO_PKG_NAME", &package);
+    env::set_var("CARGO_CRATE_NAME", crate_name.replace('-', "_"));
+
+    if let Some(bin) = &options.bin {
+        env::set_var("CARGO_BIN_NAME", bin);
+    }
+
+    if let Some(out_dir) = &options.out_dir {
+        env::set_var("OUT_DIR", out_dir);
+    }
+
+    Ok(())
+}
+
+fn list() -> Result<(), String> {
+    let discovery = discover();
+
+    let width = discovery
+        .components
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+
+    for (name, path) in discovery.components.iter() {
+        match name.as_str() {
+            "engine" => println!("{:width$}  {} (engine!)", name, path, width = width),
+            _ => {
+                let (path, cfgs) = split_cfgs(path.trim_matches('\''));
+                let component = format!("{} {}", path, cfgs);
+                println!("{:width$}  {}", name, component.trim_end(), width = width);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn check() -> Result<(), String> {
+    let _lock = RegistryLock::acquire()?;
+
+    verify_components()?;
+    println!(
+        "{} is up to date for `{}`",
+        components_toml().display(),
+        get_section_name()
+    );
+
+    Ok(())
+}
+
+fn regenerate() -> Result<(), String> {
+    match regenerate_components()? {
+        true => println!(
+            "Regenerated `{}` in {}",
+            get_section_name(),
+            components_toml().display()
+        ),
+        false => println!(
+            "{} is already up to date for `{}`",
+            components_toml().display(),
+            get_section_name()
+        ),
+    }
+
+    Ok(())
+}
+
+// Acquiring the lock already gets rid of dead or hung holders
+fn unlock(force: bool) -> Result<(), String> {
+    let path = components_lock();
+
+    if !path.exists() {
+        println!("{} doesn't exist", path.display());
+        return Ok(());
+    }
+
+    match RegistryLock::acquire_at(&path, Duration::ZERO) {
+        Ok(lock) => drop(lock),
+        Err(message) if !force => return Err(message),
+        Err(_) => {}
+    }
+
+    fs::remove_file(&path)
+        .map_err(|err| format!("Unable to remove {} : {}", path.display(), err))?;
+    println!("Removed {}", path.display());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::parse_options;
+
+    fn arguments(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn options_default_to_the_library_of_the_current_directory() {
+        let options = parse_options(arguments("list")).unwrap();
+
+        assert_eq!(options.command, "list");
+        assert_eq!(options.manifest_path, PathBuf::from("Cargo.toml"));
+        assert_eq!(
+            (options.bin, options.test, options.out_dir),
+            (None, None, None)
+        );
+        assert!(!options.force);
+    }
+
+    #[test]
+    fn options_are_read_in_any_order() {
+        let options = parse_options(arguments(
+            "unlock --force --out-dir target/out --bin tool --manifest-path game/Cargo.toml",
+        ))
+        .unwrap();
+
+        assert_eq!(options.command, "unlock");
+        assert_eq!(options.manifest_path, PathBuf::from("game/Cargo.toml"));
+        assert_eq!(options.bin.as_deref(), Some("tool"));
+        assert_eq!(options.out_dir, Some(PathBuf::from("target/out")));
+        assert!(options.force);
+
+        let options = parse_options(arguments("check --test physics")).unwrap();
+        assert_eq!(options.test.as_deref(), Some("physics"));
+    }
+
+    #[test]
+    fn bad_arguments_are_reported() {
+        let error = |line| parse_options(arguments(line)).err().unwrap();
+
+        assert_eq!(error(""), "no command given");
+        assert_eq!(error("build"), "unknown command `build`");
+        assert_eq!(error("list --verbose"), "unknown option `--verbose`");
+        assert_eq!(error("list --bin"), "`--bin` expects a value");
+    }
+}
@@ -0,0 +1,234 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    accessors::{generated_file_name, get_current_crate, manifest_dir, source_dir},
+    parsing::split_cfgs,
+    search_logic::{conflicting_engines, discover},
+};
+
+/* Registry generation from a build script, which unlike proc macros gets rerun by Cargo whenever
+   one of the sources it reads changes :
+        [build-dependencies]
+        utils = { path = "..." }
+
+        // build.rs
+        fn main() {
+            utils::build::generate();
+        }
+
+   Every target of the package gets its own OUT_DIR/ignition/<section>.rs, holding a macro which
+   calls engine!(), ecs!() or export_components!() back with the components of the target. When
+   it exists, these macros include!() it instead of going through components.toml
+*/
+pub fn generate() {
+    let out_dir = match env::var_os("OUT_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => panic!("utils::build::generate() must be called from a build script"),
+    };
+
+    let package = env::var("CARGO_PKG_NAME")
+        .unwrap_or_else(|_| get_current_crate())
+        .replace('-', "_");
+
+    for target in find_targets(&package) {
+        env::set_var("CARGO_CRATE_NAME", &target.crate_name);
+        match &target.bin_name {
+            Some(name) => env::set_var("CARGO_BIN_NAME", name),
+            None => env::remove_var("CARGO_BIN_NAME"),
+        }
+
+        let discovery = discover();
+
+        for source in discovery.sources.iter() {
+            println!("cargo:rerun-if-changed={}", source.display());
+        }
+
+        let path = out_dir.join(generated_file_name());
+        let contents = generate_callback(&discovery.components);
+
+        // Left untouched when nothing changed, so that the target isn't rebuilt for nothing
+        if fs::read_to_string(&path).ok().as_deref() != Some(contents.as_str()) {
+            let written =
+                fs::create_dir_all(path.parent().unwrap()).and_then(|_| fs::write(&path, contents));
+
+            if let Err(err) = written {
+                panic!("Unable to write to {} : {}", path.display(), err);
+            }
+        }
+    }
+
+    env::remove_var("CARGO_CRATE_NAME");
+    env::remove_var("CARGO_BIN_NAME");
+
+    // New targets don't show up in any of the sources already read
+    for dir in ["src/bin", "tests", "benches", "examples"] {
+        if manifest_dir().join(dir).is_dir() {
+            println!("cargo:rerun-if-changed={}", dir);
+        }
+    }
+}
+
+struct Target {
+    crate_name: String,
+    bin_name: Option<String>,
+}
+
+// Same layout search_logic::crate_root() expects
+fn find_targets(package: &str) -> Vec<Target> {
+    let mut targets = Vec::new();
+
+    if source_dir().join("lib.rs").is_file() {
+        targets.push(Target {
+            crate_name: package.to_string(),
+            bin_name: None,
+        });
+    }
+
+    if source_dir().join("main.rs").is_file() {
+        targets.push(Target {
+            crate_name: package.to_string(),
+            bin_name: Some(package.to_string()),
+        });
+    }
+
+    for name in find_entry_points(&source_dir().join("bin")) {
+        targets.push(Target {
+            crate_name: name.replace('-', "_"),
+            bin_name: Some(name),
+        });
+    }
+
+    for dir in ["tests", "benches", "examples"] {
+        for name in find_entry_points(&manifest_dir().join(dir)) {
+            targets.push(Target {
+                crate_name: name.replace('-', "_"),
+                bin_name: None,
+            });
+        }
+    }
+
+    targets
+}
+
+// "tests/ecs.rs" and "tests/ecs/main.rs" -> "ecs"
+fn find_entry_points(dir: &Path) -> Vec<String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut names = entries
+        .filter_map(|x| x.ok().map(|x| x.path()))
+        .filter_map(|path| match path.is_dir() {
+            true if path.join("main.rs").is_file() => path.file_name().map(|x| x.to_owned()),
+            false if path.extension().is_some_and(|x| x == "rs") => {
+                path.file_stem().map(|x| x.to_owned())
+            }
+            _ => None,
+        })
+        .map(|x| x.to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+
+    names.sort();
+    names
+}
+
+fn generate_callback(components: &[(String, String)]) -> String {
+    let current_crate = get_current_crate();
+
+    let engines = components
+        .iter()
+        .filter(|(x, _y)| x == "engine")
+        .map(|(_x, y)| y.clone())
+        .collect::<Vec<String>>();
+    let engines = conflicting_engines(&engines);
+
+    // "'game::physics::Position #[cfg(test)]'" -> "#[cfg(test)] crate::physics::Position"
+    let paths = components
+        .iter()
+        .filter(|(x, _y)| x != "engine")
+        .map(|(_x, y)| {
+            let (path, cfgs) = split_cfgs(y.trim_matches('\''));
+            let path = path.strip_prefix(&current_crate).unwrap_or(path);
+
+            format!("{} crate{}", cfgs, path).trim().to_string()
+        })
+        .collect::<Vec<String>>();
+
+    let mut callback = String::from("// Generated by utils::build::generate(), do not edit\n");
+    callback.push_str("macro_rules! ignition_registry {\n");
+
+    if engines.len() > 1 {
+        callback.push_str(&format!(
+            "    (::ignition::prelude::engine; $($arguments:tt)*) => {{\n        compile_error!({:?});\n    }};\n",
+            format!(
+                "engine!() can only be invoked once per crate, but it is invoked in each of : {}",
+                engines.join(", ")
+            )
+        ));
+    }
+
+    callback.push_str(&format!(
+        "    (:: $($callback:ident)::+ ; $($arguments:tt)*) => {{\n        :: $($callback)::+ ! {{\n            $($arguments)*\n            __generated_components: [{}],\n        }}\n    }};\n}}\n",
+        paths.join(", ")
+    ));
+
+    callback
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::{build::generate, fixture::Fixture};
+
+    #[test]
+    fn every_target_gets_a_callback_with_its_components() {
+        let fixture = Fixture::new("game");
+        fixture
+            .file(
+                "src/lib.rs",
+                "#[derive(Component)] pub struct Root;\n\
+                 #[cfg(test)] mod physics;",
+            )
+            .file(
+                "src/physics.rs",
+                "#[derive(Component)] pub struct Position;",
+            )
+            .file("src/main.rs", "mod a { engine!(); } mod b { engine!(); }")
+            .file(
+                "tests/collisions.rs",
+                "#[derive(Component)] pub struct Contact;",
+            );
+        env::set_var("OUT_DIR", fixture.dir.join("out"));
+
+        generate();
+
+        assert_eq!(
+            fixture.read("out/ignition/game.rs"),
+            "// Generated by utils::build::generate(), do not edit
+macro_rules! ignition_registry {
+    (:: $($callback:ident)::+ ; $($arguments:tt)*) => {
+        :: $($callback)::+ ! {
+            $($arguments)*
+            __generated_components: [crate::Root, #[cfg(test)] crate::physics::Position],
+        }
+    };
+}
+"
+        );
+        assert!(fixture
+            .read("out/ignition/collisions.rs")
+            .contains("__generated_components: [crate::Contact],"));
+
+        // Only engine!() is rejected, ecs!() and export_components!() get through
+        let binary = fixture.read("out/ignition/game.bin.rs");
+        assert!(binary.contains(
+            "(::ignition::prelude::engine; $($arguments:tt)*) => {\n        compile_error!(\"engine!() can only be invoked once per crate, but it is invoked in each of : game::a, game::b\");"
+        ));
+        assert!(binary.contains("__generated_components: [],"));
+    }
+}
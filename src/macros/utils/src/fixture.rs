@@ -0,0 +1,80 @@
+use std::{
+    env, fs,
+    path::PathBuf,
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use crate::accessors::tempfile;
+
+// Everything Cargo sets that changes where the registry looks
+const VARIABLES: &[&str] = &[
+    "CARGO_MANIFEST_DIR",
+    "CARGO_CRATE_NAME",
+    "CARGO_PKG_NAME",
+    "CARGO_BIN_NAME",
+    "OUT_DIR",
+];
+
+static ENVIRONMENT: Mutex<()> = Mutex::new(());
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+// A throwaway package in the temporary directory, compiled as the library `name`. The environment
+// is shared by every test of the process, so fixtures are handed out one at a time
+pub struct Fixture {
+    pub dir: PathBuf,
+    _environment: MutexGuard<'static, ()>,
+}
+
+impl Fixture {
+    pub fn new(name: &str) -> Self {
+        let environment = ENVIRONMENT.lock().unwrap_or_else(|x| x.into_inner());
+
+        let dir = env::temp_dir().join(format!(
+            "ignition-fixture-{}-{}",
+            process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+
+        for variable in VARIABLES {
+            env::remove_var(variable);
+        }
+        env::set_var("CARGO_MANIFEST_DIR", &dir);
+        env::set_var("CARGO_CRATE_NAME", name);
+        env::set_var("CARGO_PKG_NAME", name);
+
+        Fixture {
+            dir,
+            _environment: environment,
+        }
+    }
+
+    // Relative to the package : fixture.file("src/lib.rs", "mod life;")
+    pub fn file(&self, path: &str, contents: &str) -> &Self {
+        let path = self.dir.join(path);
+
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+        self
+    }
+
+    pub fn read(&self, path: &str) -> String {
+        fs::read_to_string(self.dir.join(path)).unwrap()
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(tempfile());
+        let _ = fs::remove_dir_all(&self.dir);
+
+        for variable in VARIABLES {
+            env::remove_var(variable);
+        }
+    }
+}
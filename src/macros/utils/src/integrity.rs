@@ -0,0 +1,217 @@
+use std::{fs, path::PathBuf};
+
+use crate::{
+    accessors::{get_current_section, get_section_name, manifest_dir},
+    search_logic::discover,
+};
+
+pub const COMPONENTS_HASH: &str = "components_hash";
+pub const SOURCES_HASH: &str = "sources_hash";
+
+const REGENERATE: &str = "Run `ignition-registry regenerate`, or remove the section of this crate from components.toml (or the whole file) and rebuild to regenerate it";
+
+/* HASHES */
+
+// FNV-1a, unlike DefaultHasher it is guaranteed to give the same result on every toolchain
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+pub fn hash_components(components: &[(String, String)]) -> String {
+    let lines = components
+        .iter()
+        .map(|(x, y)| format!("{} = {}\n", x, y))
+        .collect::<String>();
+
+    format!("{:016x}", fnv1a(lines.as_bytes()))
+}
+
+pub fn hash_sources(sources: &[PathBuf]) -> String {
+    let mut contents = Vec::new();
+
+    let manifest_dir = manifest_dir();
+
+    // Relative paths joined with '/', so that the hash doesn't depend on where nor on which OS
+    // the crate is checked out
+    for source in sources {
+        let path = source
+            .strip_prefix(&manifest_dir)
+            .unwrap_or(source)
+            .components()
+            .map(|x| x.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        contents.extend(path.as_bytes());
+        contents.extend(fs::read(source).unwrap_or_default());
+    }
+
+    format!("{:016x}", fnv1a(&contents))
+}
+
+/* VERIFICATION */
+
+pub fn verify_components() -> Result<(), String> {
+    let current_crate = get_section_name();
+
+    let section = match get_current_section() {
+        Some(section) => section,
+        None => {
+            return Err(format!(
+                "components.toml has no entry for crate `{}`. {}",
+                current_crate, REGENERATE
+            ))
+        }
+    };
+
+    let (components, sources_hash) = check_components_hash(&section)?;
+
+    let discovery = discover();
+    if sources_hash == Some(hash_sources(&discovery.sources)) {
+        return Ok(());
+    }
+
+    // The sources changed since the registry was written, which only matters if their
+    // components did too
+    let removed = components
+        .iter()
+        .filter(|x| !discovery.components.contains(x))
+        .map(|(x, y)| {
+            format!(
+                "\n  - `{}` ({}) is listed in components.toml but no longer exists in the sources",
+                x,
+                y.trim_matches('\'')
+            )
+        });
+    let added = discovery
+        .components
+        .iter()
+        .filter(|x| !components.contains(x))
+        .map(|(x, y)| {
+            format!(
+                "\n  - `{}` ({}) exists in the sources but isn't listed in components.toml",
+                x,
+                y.trim_matches('\'')
+            )
+        });
+
+    let differences = removed.chain(added).collect::<String>();
+    match differences.is_empty() {
+        true => Ok(()),
+        false => Err(format!(
+            "components.toml is out of date with the sources of crate `{}` :{}\n{}",
+            current_crate, differences, REGENERATE
+        )),
+    }
+}
+
+// Run before the registry gets rewritten too, so that hand edits are reported instead of silently
+// overwritten. A crate without a section has nothing to lose
+pub fn verify_components_hash() -> Result<(), String> {
+    match get_current_section() {
+        Some(section) => check_components_hash(&section).map(|_| ()),
+        None => Ok(()),
+    }
+}
+
+// The components listed in a section, along with the hash of the sources they came from
+type Section = (Vec<(String, String)>, Option<String>);
+
+fn check_components_hash(section: &str) -> Result<Section, String> {
+    let mut components = Vec::new();
+    let mut components_hash = None;
+    let mut sources_hash = None;
+
+    for line in section.lines() {
+        let (key, value) = match line.split_once(" = ") {
+            Some(pair) => pair,
+            None => continue,
+        };
+
+        match key {
+            COMPONENTS_HASH => components_hash = Some(value.to_string()),
+            SOURCES_HASH => sources_hash = Some(value.to_string()),
+            _ => components.push((key.to_string(), value.to_string())),
+        }
+    }
+
+    if components_hash != Some(hash_components(&components)) {
+        return Err(format!(
+            "The components of crate `{}` in components.toml have been edited by hand or were generated by an older version of ignition. {}",
+            get_section_name(), REGENERATE
+        ));
+    }
+
+    Ok((components, sources_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{fixture::Fixture, integrity::verify_components, regenerate_components};
+
+    fn fixture() -> Fixture {
+        let fixture = Fixture::new("game");
+        fixture.file("src/lib.rs", "#[derive(Component)] pub struct Position;");
+
+        regenerate_components().unwrap();
+        fixture
+    }
+
+    #[test]
+    fn freshly_generated_components_are_verified() {
+        let _fixture = fixture();
+
+        assert_eq!(verify_components(), Ok(()));
+    }
+
+    #[test]
+    fn missing_sections_are_reported() {
+        let _fixture = Fixture::new("game");
+
+        let error = verify_components().unwrap_err();
+        assert!(error.contains("has no entry for crate `game`"), "{}", error);
+    }
+
+    #[test]
+    fn hand_edited_components_are_reported() {
+        let fixture = fixture();
+
+        let registry = fixture.read("components.toml");
+        fixture.file(
+            "components.toml",
+            &registry.replace("game::Position", "game::Velocity"),
+        );
+
+        let error = verify_components().unwrap_err();
+        assert!(error.contains("edited by hand"), "{}", error);
+    }
+
+    #[test]
+    fn only_changes_to_the_components_of_the_sources_are_reported() {
+        let fixture = fixture();
+
+        fixture.file(
+            "src/lib.rs",
+            "// Moved around\n#[derive(Component)] pub struct Position;",
+        );
+        assert_eq!(verify_components(), Ok(()));
+
+        fixture.file("src/lib.rs", "#[derive(Component)] pub struct Velocity;");
+
+        let error = verify_components().unwrap_err();
+        assert!(
+            error.contains(
+                "`Position` (game::Position) is listed in components.toml but no longer exists"
+            ),
+            "{}",
+            error
+        );
+        assert!(
+            error.contains("`Velocity` (game::Velocity) exists in the sources but isn't listed"),
+            "{}",
+            error
+        );
+    }
+}
@@ -1,62 +1,108 @@
 use std::fs;
 
-use regex::Regex;
-
 pub mod accessors;
+pub mod build;
+pub mod integrity;
+pub mod lock;
 pub mod parsing;
 pub mod search_logic;
 
+#[cfg(test)]
+mod fixture;
+
 use crate::{
     accessors::{
-        components_are_locked, get_components, get_current_crate, get_current_time,
-        get_time_since_last_update, tempfile,
+        components_toml, generated_components, get_components, get_current_section,
+        get_current_time, get_section_name, get_time_since_last_update, section_regex, tempfile,
     },
-    search_logic::find_components,
+    integrity::{
+        hash_components, hash_sources, verify_components, verify_components_hash, COMPONENTS_HASH,
+        SOURCES_HASH,
+    },
+    lock::RegistryLock,
+    parsing::parse_components,
+    search_logic::{discover, Discovery},
 };
 
-pub fn update_components() -> Option<Vec<(String, String)>> {
-    if !components_are_locked() {
-        let components = search_and_rescue_components();
-        fs::remove_file("components.lock").unwrap();
+// Holds the lock while reading components.toml too, so a concurrent update is never half-read
+pub fn get_verified_components() -> Result<Vec<(String, String)>, String> {
+    let _lock = RegistryLock::acquire()?;
+
+    match search_and_rescue_components()? {
+        Some(components) => Ok(components),
+        None => verify_components().map(|_| parse_components()),
+    }
+}
 
-        return components;
+// Nothing to keep up to date when the build script generates the components
+pub fn update_components() -> Result<Option<Vec<(String, String)>>, String> {
+    if generated_components().is_some() {
+        return Ok(None);
     }
 
-    None
+    let _lock = RegistryLock::acquire()?;
+
+    search_and_rescue_components()
 }
 
-pub fn search_and_rescue_components() -> Option<Vec<(String, String)>> {
+// Ignores the 2 seconds window, and leaves the file untouched (timestamp included) when its
+// components are already up to date. Returns whether components.toml was rewritten
+pub fn regenerate_components() -> Result<bool, String> {
+    let _lock = RegistryLock::acquire()?;
+
+    let discovery = discover();
+    let formatted_components = format_components(&discovery);
+
+    let (_header, section) = formatted_components.split_once('\n').unwrap();
+    if get_current_section().as_deref() == Some(section) {
+        return Ok(false);
+    }
+
+    write_to_component_file(replace_components_in_file(formatted_components));
+    Ok(true)
+}
+
+pub fn search_and_rescue_components() -> Result<Option<Vec<(String, String)>>, String> {
     if get_current_time() - get_time_since_last_update() > 2 {
-        let components = find_components();
-        let components_file = package_components_for_filing(&components);
+        verify_components_hash()?;
+
+        let discovery = discover();
+        let components_file = package_components_for_filing(&discovery);
         write_to_component_file(components_file);
 
-        let components = components.into_iter().filter(|(_x, y)| y.contains("'"));
-        return Some(components.collect());
+        // Same shape as parse_components(), without the engine and the quotes
+        let components = discovery
+            .components
+            .into_iter()
+            .filter(|(_x, y)| y.contains("'"))
+            .map(|(x, y)| (x, y.trim_matches('\'').to_string()));
+        return Ok(Some(components.collect()));
     }
 
-    None
+    Ok(None)
 }
 
-pub fn package_components_for_filing(components: &Vec<(String, String)>) -> String {
-    let formatted_components = format_components(&components);
-    let components_file = replace_components_in_file(formatted_components);
+pub fn package_components_for_filing(discovery: &Discovery) -> String {
+    let formatted_components = format_components(discovery);
 
-    components_file
+    replace_components_in_file(formatted_components)
 }
 
-pub fn format_components(components: &Vec<(String, String)>) -> String {
-    let mut formatted_components = components
-        .iter()
-        .map(|(x, y)| format!("{} = {}", x, y))
-        .collect::<Vec<String>>()
-        .join("\n");
-
-    let crate_name = get_current_crate();
+pub fn format_components(discovery: &Discovery) -> String {
+    let section = get_section_name();
     let time = get_current_time();
 
-    formatted_components.insert_str(0, &format!("[[{}.{}]]\n", crate_name, time));
-    formatted_components.push('\n');
+    let mut formatted_components = format!("[[{}.{}]]\n", section, time);
+
+    for (x, y) in discovery.components.iter() {
+        formatted_components.push_str(&format!("{} = {}\n", x, y));
+    }
+
+    let components_hash = hash_components(&discovery.components);
+    let sources_hash = hash_sources(&discovery.sources);
+
+    formatted_components.push_str(&format!("{} = {}\n", COMPONENTS_HASH, components_hash));
+    formatted_components.push_str(&format!("{} = {}\n", SOURCES_HASH, sources_hash));
 
     formatted_components
 }
@@ -68,23 +114,48 @@ pub fn replace_components_in_file(formatted: String) -> String {
         return formatted;
     }
 
-    let cur_crate = get_current_crate();
-    let regex = Regex::new(&format!(r"\[\[{}.\d*\]\]\n(?:.* = .*\n)*", cur_crate)).unwrap();
+    let regex = section_regex();
 
-    let new_components_file = match regex.is_match(&old_components_file) {
+    match regex.is_match(&old_components_file) {
         true => regex.replace(&old_components_file, formatted).to_string(),
         false => old_components_file + "\n" + &formatted,
-    };
-
-    new_components_file
+    }
 }
 
 pub fn write_to_component_file(components: String) {
-    if let Err(_) = fs::write("components.toml", components) {
-        println!("Unable to write to components.toml")
+    if fs::write(components_toml(), components).is_err() {
+        println!("Unable to write to {}", components_toml().display())
     }
 
-    if let Err(_) = fs::copy("components.toml", tempfile()) {
+    if fs::copy(components_toml(), tempfile()).is_err() {
         println!("Unable to copy list of components to temporary file");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use crate::{fixture::Fixture, update_components};
+
+    #[test]
+    fn hand_edits_are_reported_instead_of_overwritten() {
+        let fixture = Fixture::new("game");
+        fixture.file("src/lib.rs", "#[derive(Component)] pub struct Position;");
+
+        update_components().unwrap();
+
+        // Also moves the last update out of the 2 seconds window, so the registry gets rewritten
+        let registry = fixture.read("components.toml");
+        let edited = Regex::new(r"\[\[game\.\d*\]\]")
+            .unwrap()
+            .replace(&registry, "[[game.0]]")
+            .replace("game::Position", "game::Velocity");
+        fixture.file("components.toml", &edited);
+
+        let error = update_components().unwrap_err();
+
+        assert!(error.contains("edited by hand"), "{}", error);
+        assert_eq!(fixture.read("components.toml"), edited);
+    }
+}
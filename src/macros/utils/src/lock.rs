@@ -0,0 +1,264 @@
+use std::{
+    fs,
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+    process, thread,
+    time::{Duration, Instant},
+};
+
+use fs2::{lock_contended_error, FileExt};
+
+use crate::accessors::{components_lock, get_current_time};
+
+// How long a macro waits for another rustc process to finish updating components.toml
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+// A holder that kept the lock for that long is assumed to hang, an update takes milliseconds
+pub const STALE_AFTER: u64 = 60;
+
+const RETRY_EVERY: Duration = Duration::from_millis(20);
+
+/* The lock is an advisory lock (flock / LockFileEx) on components.lock, so the OS releases it
+   when a build is killed. The file itself is never removed on release, only its content is :
+        <pid> <time of acquisition>
+   which tells waiting processes who holds the lock and since when. Should the holder be dead or
+   hung, the file is removed and a fresh one is locked in its place.
+*/
+pub struct RegistryLock {
+    file: File,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holder {
+    pub pid: u32,
+    pub since: u64,
+}
+
+impl RegistryLock {
+    pub fn acquire() -> Result<Self, String> {
+        Self::acquire_at(&components_lock(), LOCK_TIMEOUT)
+    }
+
+    pub fn acquire_at(path: &Path, timeout: Duration) -> Result<Self, String> {
+        let start = Instant::now();
+
+        loop {
+            let mut file = open(path)?;
+
+            match file.try_lock_exclusive() {
+                // Another process may have removed a stale lock file between open and lock, in
+                // which case we hold a lock on a file nobody else will ever see
+                Ok(()) if is_same_file(&file, path) => {
+                    write_holder(&mut file, path)?;
+                    return Ok(Self { file });
+                }
+                Ok(()) => continue,
+                Err(err) if err.kind() == lock_contended_error().kind() => {}
+                Err(err) => return Err(format!("Unable to lock {} : {}", path.display(), err)),
+            }
+
+            let holder = read_holder(&mut file);
+
+            if holder.as_ref().is_some_and(|x| x.is_stale()) {
+                let _ = fs::remove_file(path);
+                continue;
+            }
+
+            if start.elapsed() >= timeout {
+                return Err(match holder {
+                    Some(holder) => format!(
+                        "Timed out after {}s waiting for {} held by process {} since {}s. If no other build is running, remove {}",
+                        timeout.as_secs(),
+                        path.display(),
+                        holder.pid,
+                        get_current_time().saturating_sub(holder.since),
+                        path.display()
+                    ),
+                    None => format!(
+                        "Timed out after {}s waiting for {}. If no other build is running, remove {}",
+                        timeout.as_secs(),
+                        path.display(),
+                        path.display()
+                    ),
+                });
+            }
+
+            thread::sleep(RETRY_EVERY);
+        }
+    }
+}
+
+impl Drop for RegistryLock {
+    fn drop(&mut self) {
+        let _ = self.file.set_len(0);
+        let _ = self.file.unlock();
+    }
+}
+
+impl Holder {
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut fields = content.split_whitespace();
+
+        let pid = fields.next()?.parse().ok()?;
+        let since = fields.next()?.parse().ok()?;
+
+        Some(Self { pid, since })
+    }
+
+    pub fn is_stale(&self) -> bool {
+        !process_is_alive(self.pid) || get_current_time().saturating_sub(self.since) > STALE_AFTER
+    }
+}
+
+fn open(path: &Path) -> Result<File, String> {
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)
+        .map_err(|err| format!("Unable to open {} : {}", path.display(), err))
+}
+
+fn write_holder(file: &mut File, path: &Path) -> Result<(), String> {
+    file.set_len(0)
+        .and_then(|_| file.seek(SeekFrom::Start(0)))
+        .and_then(|_| write!(file, "{} {}", process::id(), get_current_time()))
+        .map_err(|err| format!("Unable to write to {} : {}", path.display(), err))
+}
+
+fn read_holder(file: &mut File) -> Option<Holder> {
+    let mut content = String::new();
+
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut content).ok()?;
+
+    Holder::parse(&content)
+}
+
+#[cfg(unix)]
+fn is_same_file(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (file.metadata(), fs::metadata(path)) {
+        (Ok(x), Ok(y)) => x.dev() == y.dev() && x.ino() == y.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_file: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+#[cfg(target_os = "linux")]
+fn process_is_alive(pid: u32) -> bool {
+    Path::new(&format!("/proc/{}", pid)).exists()
+}
+
+// Without procfs only the age of the lock can tell whether it is stale
+#[cfg(not(target_os = "linux"))]
+fn process_is_alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, fs::File, io::Write, process, time::Duration};
+
+    use fs2::FileExt;
+
+    use crate::{
+        accessors::get_current_time,
+        fixture::Fixture,
+        lock::{Holder, RegistryLock, STALE_AFTER},
+    };
+
+    // Locked through its own handle, like another process would
+    fn hold(fixture: &Fixture, content: &str) -> File {
+        let mut file = File::create(fixture.dir.join("components.lock")).unwrap();
+        file.lock_exclusive().unwrap();
+        write!(file, "{}", content).unwrap();
+
+        file
+    }
+
+    #[test]
+    fn holders_are_parsed_from_the_lock_file() {
+        assert_eq!(
+            Holder::parse("42 1700000000"),
+            Some(Holder {
+                pid: 42,
+                since: 1700000000
+            })
+        );
+        assert_eq!(Holder::parse(""), None);
+        assert_eq!(Holder::parse("42"), None);
+        assert_eq!(Holder::parse("pid 1700000000"), None);
+    }
+
+    #[test]
+    fn the_holder_is_written_while_the_lock_is_held() {
+        let fixture = Fixture::new("game");
+        let path = fixture.dir.join("components.lock");
+
+        let lock = RegistryLock::acquire_at(&path, Duration::from_secs(1)).unwrap();
+        let holder = Holder::parse(&fixture.read("components.lock")).unwrap();
+        assert_eq!(holder.pid, process::id());
+        assert!(!holder.is_stale());
+
+        drop(lock);
+        assert_eq!(fixture.read("components.lock"), "");
+        assert!(RegistryLock::acquire_at(&path, Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn waiting_on_a_live_holder_times_out() {
+        let fixture = Fixture::new("game");
+        let _held = hold(
+            &fixture,
+            &format!("{} {}", process::id(), get_current_time()),
+        );
+
+        let error = RegistryLock::acquire_at(
+            &fixture.dir.join("components.lock"),
+            Duration::from_millis(100),
+        )
+        .err()
+        .unwrap();
+
+        assert!(error.contains("Timed out"), "{}", error);
+        assert!(
+            error.contains(&format!("held by process {}", process::id())),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn hung_holders_are_replaced() {
+        let fixture = Fixture::new("game");
+        let since = get_current_time() - STALE_AFTER - 1;
+        let _held = hold(&fixture, &format!("{} {}", process::id(), since));
+
+        let path = fixture.dir.join("components.lock");
+        let _lock = RegistryLock::acquire_at(&path, Duration::from_secs(1)).unwrap();
+
+        let holder = Holder::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(holder.since > since);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn dead_holders_are_replaced() {
+        let fixture = Fixture::new("game");
+        let _held = hold(&fixture, &format!("{} {}", u32::MAX, get_current_time()));
+
+        let path = fixture.dir.join("components.lock");
+        let _lock = RegistryLock::acquire_at(&path, Duration::from_secs(1)).unwrap();
+
+        let holder = Holder::parse(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(holder.pid, process::id());
+    }
+}
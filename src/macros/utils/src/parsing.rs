@@ -3,10 +3,10 @@ use lazy_static::lazy_static;
 use proc_macro2::{Ident, Span};
 use regex::Regex;
 
-use crate::accessors::{get_components, get_current_crate};
+use crate::accessors::{get_current_crate, get_current_section};
 
 pub fn parse_components() -> Vec<(String, String)> {
-    let components = get_components();
+    let components = get_current_section().unwrap_or_default();
 
     lazy_static! {
         static ref RE: Regex = Regex::new(r"(.*) = '(.*)'").unwrap();
@@ -17,17 +17,44 @@ pub fn parse_components() -> Vec<(String, String)> {
         .collect::<Vec<(String, String)>>()
 }
 
-pub fn get_path_of_engine_in_current_crate() -> String {
-    let regex = Regex::new(&format!(r"(?m)engine = {}()$", get_current_crate())).unwrap();
-    if let Some(cap) = regex.captures(&get_components()) {
-        return format!("{}{}", get_current_crate(), cap[1].to_string());
+// Along with the #[cfg]s they are compiled under : "#[cfg(test)] life::Name"
+pub fn get_components_of_current_crate(components: &[(String, String)]) -> Vec<String> {
+    let current_crate = get_current_crate();
+
+    components
+        .iter()
+        .filter_map(|(name, path)| {
+            let (path, cfgs) = split_cfgs(path);
+            let (module, _name) = path.rsplit_once("::")?; // "ignition::life::Name" -> "ignition::life"
+
+            let path = match module == current_crate {
+                true => name.clone(),
+                false => module
+                    .strip_prefix(&format!("{}::", current_crate))
+                    .map(|x| format!("{}::{}", x, name))?, // "life::Name"
+            };
+
+            Some(format!("{} {}", cfgs, path).trim_start().to_string())
+        })
+        .collect()
+}
+
+// "ignition::life::Name #[cfg(test)]" -> ("ignition::life::Name", "#[cfg(test)]")
+pub fn split_cfgs(registered: &str) -> (&str, &str) {
+    match registered.find(" #[") {
+        Some(end) => (&registered[..end], &registered[end + 1..]),
+        None => (registered, ""),
     }
+}
 
-    String::new()
+// Hidden module generated by #[derive(Component)] next to each component, holding the trait
+// engine!() implements to hand out its pool : "Position" -> "__ignition_Position"
+pub fn to_accessor_module(component: &Ident) -> Ident {
+    to_ident(&format!("__ignition_{}", component))
 }
 
-pub fn to_ident(string: &String) -> Ident {
-    Ident::new(string.as_str(), Span::call_site())
+pub fn to_ident(string: &str) -> Ident {
+    Ident::new(string, Span::call_site())
 }
 
 pub fn to_snakecased_ident(string: &String) -> Ident {
@@ -36,3 +63,39 @@ pub fn to_snakecased_ident(string: &String) -> Ident {
         Span::call_site(),
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fixture::Fixture, parsing::get_components_of_current_crate, search_logic::discover,
+    };
+
+    #[test]
+    fn components_of_the_current_crate_keep_their_cfgs() {
+        let fixture = Fixture::new("game");
+        fixture
+            .file(
+                "src/lib.rs",
+                "#[derive(Component)] pub struct Position;\n\
+                 #[cfg(feature = \"editor\")] mod editor;",
+            )
+            .file(
+                "src/editor.rs",
+                "#[cfg(test)] #[derive(Component)] pub struct Gizmo;",
+            );
+
+        let components = discover()
+            .components
+            .into_iter()
+            .map(|(x, y)| (x, y.trim_matches('\'').to_string()))
+            .collect::<Vec<(String, String)>>();
+
+        assert_eq!(
+            get_components_of_current_crate(&components),
+            vec![
+                "Position".to_string(),
+                "#[cfg(feature = \"editor\")] #[cfg(test)] editor::Gizmo".to_string(),
+            ]
+        );
+    }
+}
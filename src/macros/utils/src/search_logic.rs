@@ -1,63 +1,506 @@
-use std::{ffi::OsStr, fs, path::PathBuf};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 
-use lazy_static::lazy_static;
-use regex::{Captures, Regex};
+use quote::quote;
+use syn::{Attribute, Item, ItemMod, Lit, Meta, NestedMeta};
 
-use crate::accessors::{get_component_module_path, get_module_path, source_dir};
+use crate::{
+    accessors::{get_current_crate, manifest_dir, source_dir},
+    parsing::split_cfgs,
+};
+
+#[derive(Default)]
+pub struct Discovery {
+    pub components: Vec<(String, String)>,
+    pub sources: Vec<PathBuf>,
+}
 
 pub fn find_components() -> Vec<(String, String)> {
-    let mut components = Vec::new();
-    scan_dir_for_components(&source_dir(), &mut components);
+    discover().components
+}
+
+// Module paths of the engine!() of the crate that end up compiled together
+pub fn find_engine_invocations() -> Vec<String> {
+    let engines = discover()
+        .components
+        .into_iter()
+        .filter(|(x, _y)| x == "engine")
+        .map(|(_x, y)| y)
+        .collect::<Vec<String>>();
+
+    conflicting_engines(&engines)
+}
+
+// Only the engines which could be compiled alongside another one. Those under opposite #[cfg]s,
+// like cfg(test) and cfg(not(test)), never are
+pub fn conflicting_engines(engines: &[String]) -> Vec<String> {
+    engines
+        .iter()
+        .enumerate()
+        .filter(|(i, x)| {
+            engines
+                .iter()
+                .enumerate()
+                .any(|(j, y)| *i != j && !are_exclusive(x, y))
+        })
+        .map(|(_i, x)| x.clone())
+        .collect()
+}
+
+// "game #[cfg(test)]" and "game::app #[cfg(not(test))]" are
+fn are_exclusive(engine: &str, other: &str) -> bool {
+    let predicates = |engine| {
+        let (_path, cfgs) = split_cfgs(engine);
+
+        cfgs.split("#[cfg(")
+            .filter_map(|x| x.trim().strip_suffix(")]"))
+            .map(String::from)
+            .collect::<Vec<String>>()
+    };
+    let (predicates, others) = (predicates(engine), predicates(other));
 
-    components
+    predicates
+        .iter()
+        .any(|x| others.contains(&format!("not({})", x)))
+        || others
+            .iter()
+            .any(|x| predicates.contains(&format!("not({})", x)))
 }
 
-pub fn scan_dir_for_components(dir: &PathBuf, components: &mut Vec<(String, String)>) {
-    for entry in fs::read_dir(dir).unwrap() {
-        let path = entry.unwrap().path();
+pub fn discover() -> Discovery {
+    let mut discovery = Discovery::default();
 
-        if path.is_file() {
-            get_components_from_file(&path, components);
-        } else if path.file_name() != Some(OsStr::new("macros")) {
-            scan_dir_for_components(&path, components);
+    if let Some(root) = crate_root() {
+        let children_dir = root.parent().unwrap_or(Path::new(".")).to_path_buf();
+        let (crate_name, cfgs) = (get_current_crate(), Vec::new());
+        scan_file_for_components(&root, &children_dir, &crate_name, &cfgs, &mut discovery);
+    }
+
+    discovery
+}
+
+/* Cargo doesn't tell which file rustc is compiling, the usual layout of each target is assumed :
+        library             src/lib.rs
+        binary              src/main.rs, src/bin/<name>.rs or src/bin/<name>/main.rs
+        test, bench, ...    tests/<name>.rs or tests/<name>/main.rs
+*/
+pub fn crate_root() -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+
+    if let Ok(name) = env::var("CARGO_BIN_NAME") {
+        candidates.push(source_dir().join("bin").join(format!("{}.rs", name)));
+        candidates.push(source_dir().join("bin").join(&name).join("main.rs"));
+        candidates.push(source_dir().join("main.rs"));
+    } else if !is_library() {
+        let name = get_current_crate();
+
+        for dir in ["tests", "benches", "examples"] {
+            let dir = manifest_dir().join(dir);
+
+            candidates.push(dir.join(format!("{}.rs", name)));
+            candidates.push(dir.join(&name).join("main.rs"));
         }
     }
+
+    // Also covers libraries renamed in Cargo.toml
+    candidates.push(source_dir().join("lib.rs"));
+    candidates.push(source_dir().join("main.rs"));
+
+    candidates.into_iter().find(|x| x.is_file())
+}
+
+fn is_library() -> bool {
+    match env::var("CARGO_PKG_NAME") {
+        Ok(package) => package.replace('-', "_") == get_current_crate(),
+        Err(_) => true,
+    }
+}
+
+/* Modules are followed the same way rustc does:
+        src/lib.rs          mod life;           -> src/life.rs or src/life/mod.rs
+        src/life.rs         mod genesis;        -> src/life/genesis.rs or src/life/genesis/mod.rs
+        src/life.rs         mod inline { .. }   -> scanned in place, its children live in src/life/inline/
+        src/life.rs         #[path = "x.rs"]    -> src/x.rs (relative to the directory of the file)
+
+   Along the way, the #[cfg] of each module is handed down to the components it holds
+*/
+pub fn scan_file_for_components(
+    path: &Path,
+    children_dir: &Path,
+    module_path: &str,
+    cfgs: &[String],
+    discovery: &mut Discovery,
+) {
+    let file = match fs::read_to_string(path).map(|src| syn::parse_file(&src)) {
+        Ok(Ok(file)) => file,
+        _ => return, // rustc will complain about it far better than we could
+    };
+
+    discovery.sources.push(path.to_path_buf());
+
+    let path_dir = path.parent().unwrap_or(Path::new("."));
+    scan_items_for_components(
+        &file.items,
+        path_dir,
+        children_dir,
+        module_path,
+        cfgs,
+        discovery,
+    );
 }
 
-pub fn get_components_from_file(path: &PathBuf, components: &mut Vec<(String, String)>) {
-    /* Catches names of structs definitions similar to:
-            #[derive(Debug, Conponent)] // .*#\[derive(.*Conponent.*)\].*\n
-            pub struct Int { // .*struct (.*)[\{\(]
-                int: i32,
+pub fn scan_items_for_components(
+    items: &[Item],
+    path_dir: &Path,
+    children_dir: &Path,
+    module_path: &str,
+    cfgs: &[String],
+    discovery: &mut Discovery,
+) {
+    for item in items {
+        match item {
+            Item::Struct(x) if is_component(&x.attrs) && x.generics.params.is_empty() => {
+                let cfgs = [cfgs, &get_cfgs(&x.attrs)].concat();
+                push_component(&x.ident.to_string(), module_path, &cfgs, discovery)
+            }
+            Item::Enum(x) if is_component(&x.attrs) && x.generics.params.is_empty() => {
+                let cfgs = [cfgs, &get_cfgs(&x.attrs)].concat();
+                push_component(&x.ident.to_string(), module_path, &cfgs, discovery)
+            }
+            Item::Macro(x) if is_engine_invocation(&x.mac.path) => {
+                let cfgs = [cfgs, &get_cfgs(&x.attrs)].concat();
+                push_engine(module_path, &cfgs, discovery)
+            }
+            Item::Mod(x) => {
+                let cfgs = [cfgs, &get_cfgs(&x.attrs)].concat();
+                scan_module_for_components(x, path_dir, children_dir, module_path, &cfgs, discovery)
             }
+            _ => {}
+        }
+    }
+}
+
+pub fn scan_module_for_components(
+    module: &ItemMod,
+    path_dir: &Path,
+    children_dir: &Path,
+    module_path: &str,
+    cfgs: &[String],
+    discovery: &mut Discovery,
+) {
+    let name = module.ident.to_string();
+    let module_path = format!("{}::{}", module_path, name);
+    let module_dir = children_dir.join(&name);
+
+    if let Some((_, items)) = &module.content {
+        return scan_items_for_components(
+            items,
+            &module_dir,
+            &module_dir,
+            &module_path,
+            cfgs,
+            discovery,
+        );
+    }
+
+    if let Some(path) = get_path_attribute(&module.attrs) {
+        let path = path_dir.join(path);
+        let children_dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+
+        return scan_file_for_components(&path, &children_dir, &module_path, cfgs, discovery);
+    }
+
+    let candidates = [
+        children_dir.join(format!("{}.rs", name)),
+        module_dir.join("mod.rs"),
+    ];
 
-            #[derive(Debug, Conponent)] // .*#\[derive(.*Conponent.*)\].*\n
-            pub struct Float(f32) // .*struct (.*)[\{\(]
-    */
-    lazy_static! {
-        static ref RE: Regex =
-            Regex::new(r".*#\[derive(.*Component.*)\].*\n.*struct (.*)[\{\(]").unwrap();
+    if let Some(path) = candidates.iter().find(|x| x.is_file()) {
+        scan_file_for_components(path, &module_dir, &module_path, cfgs, discovery);
     }
+}
+
+/* ATTRIBUTES */
+
+pub fn is_component(attributes: &[Attribute]) -> bool {
+    attributes
+        .iter()
+        .filter(|x| x.path.is_ident("derive"))
+        .filter_map(|x| match x.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .any(|x| match x {
+            NestedMeta::Meta(Meta::Path(path)) => {
+                path.segments.last().is_some_and(|x| x.ident == "Component")
+            }
+            _ => false,
+        })
+}
 
-    let src = fs::read_to_string(path).unwrap();
+// #[cfg(all(unix, feature = "editor"))] -> "#[cfg(all(unix, feature = \"editor\"))]"
+pub fn get_cfgs(attributes: &[Attribute]) -> Vec<String> {
+    attributes
+        .iter()
+        .filter(|x| x.path.is_ident("cfg"))
+        .filter_map(|x| match x.parse_meta() {
+            Ok(Meta::List(list)) if list.nested.len() == 1 => {
+                Some(format!("#[cfg({})]", format_nested_meta(&list.nested[0])))
+            }
+            _ => None, // rustc rejects these anyway
+        })
+        .collect()
+}
 
-    if src.contains("engine!(") {
-        components.push((String::from("engine"), get_module_path(&path)));
+// Written the way rustfmt would, so that the registry reads like the sources
+fn format_nested_meta(meta: &NestedMeta) -> String {
+    match meta {
+        NestedMeta::Meta(Meta::Path(path)) => format_path(path),
+        NestedMeta::Meta(Meta::NameValue(x)) => {
+            let lit = &x.lit;
+            format!("{} = {}", format_path(&x.path), quote!(#lit))
+        }
+        NestedMeta::Meta(Meta::List(x)) => format!(
+            "{}({})",
+            format_path(&x.path),
+            x.nested
+                .iter()
+                .map(format_nested_meta)
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+        NestedMeta::Lit(lit) => quote!(#lit).to_string(),
     }
+}
+
+fn format_path(path: &syn::Path) -> String {
+    quote!(#path).to_string().replace(' ', "")
+}
+
+pub fn get_path_attribute(attributes: &[Attribute]) -> Option<String> {
+    attributes
+        .iter()
+        .filter(|x| x.path.is_ident("path"))
+        .find_map(|x| match x.parse_meta() {
+            Ok(Meta::NameValue(meta)) => match meta.lit {
+                Lit::Str(path) => Some(path.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+}
+
+pub fn is_engine_invocation(path: &syn::Path) -> bool {
+    path.segments.last().is_some_and(|x| x.ident == "engine")
+}
 
-    for cap in RE.captures_iter(&src) {
-        let name = get_component_name(cap);
-        let module_path = get_component_module_path(&path, &name);
+/* FORMATTING */
 
-        components.push((name, format!("\'{}\'", module_path)));
+// "'ignition::life::Name #[cfg(test)]'", the #[cfg] being left out when there's none
+pub fn push_component(name: &str, module_path: &str, cfgs: &[String], discovery: &mut Discovery) {
+    let mut path = format!("{}::{}", module_path, name);
+    for cfg in cfgs {
+        path.push_str(&format!(" {}", cfg));
     }
+
+    discovery
+        .components
+        .push((name.to_string(), format!("\'{}\'", path)));
 }
 
-pub fn get_component_name(capture: Captures) -> String {
-    String::from(&capture[2]).trim_matches(' ').to_string()
+// "game #[cfg(test)]", unquoted so that it can't be mistaken for a component
+pub fn push_engine(module_path: &str, cfgs: &[String], discovery: &mut Discovery) {
+    let mut path = module_path.to_string();
+    for cfg in cfgs {
+        path.push_str(&format!(" {}", cfg));
+    }
+
+    discovery.components.push((String::from("engine"), path));
 }
 
-pub fn add_component_to_module_path(mut module_path: String, name: &String) -> String {
-    module_path.push_str(&format!("::{{{}, {}Trait}}", name, name)); // "ignition::life::genesis::{Name, NameTrait}"
-    module_path
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use crate::{
+        fixture::Fixture,
+        search_logic::{crate_root, discover, find_engine_invocations},
+    };
+
+    #[test]
+    fn modules_are_followed_the_way_rustc_does() {
+        let fixture = Fixture::new("game");
+        fixture
+            .file(
+                "src/lib.rs",
+                r#"
+                #[derive(Component)] pub struct Root;
+                #[derive(Component)] pub struct Generic<T>(T);
+                #[derive(Debug)] pub struct Plain;
+
+                mod physics;
+                mod render;
+                mod inline {
+                    #[derive(Component)] pub struct Inline;
+                    mod nested;
+                }
+                #[path = "other/place.rs"] mod moved;
+                #[cfg(feature = "editor")] mod editor;
+
+                engine!();
+                "#,
+            )
+            .file(
+                "src/physics.rs",
+                "#[derive(Component)] pub struct Position; mod body;",
+            )
+            .file(
+                "src/physics/body.rs",
+                "#[derive(Component)] pub struct Body;",
+            )
+            .file(
+                "src/render/mod.rs",
+                "#[derive(ignition::prelude::Component)] pub enum Mode { Lit }",
+            )
+            .file(
+                "src/inline/nested.rs",
+                "#[derive(Component)] pub struct Nested;",
+            )
+            .file(
+                "src/other/place.rs",
+                "#[derive(Component)] pub struct Moved; mod child;",
+            )
+            .file(
+                "src/other/child.rs",
+                "#[derive(Component)] pub struct Child;",
+            )
+            .file(
+                "src/editor.rs",
+                "#[cfg(test)] #[derive(Component)] pub struct Gizmo;",
+            );
+
+        let expected = [
+            ("Root", "'game::Root'"),
+            ("Position", "'game::physics::Position'"),
+            ("Body", "'game::physics::body::Body'"),
+            ("Mode", "'game::render::Mode'"),
+            ("Inline", "'game::inline::Inline'"),
+            ("Nested", "'game::inline::nested::Nested'"),
+            ("Moved", "'game::moved::Moved'"),
+            ("Child", "'game::moved::child::Child'"),
+            (
+                "Gizmo",
+                "'game::editor::Gizmo #[cfg(feature = \"editor\")] #[cfg(test)]'",
+            ),
+            ("engine", "game"),
+        ];
+
+        assert_eq!(
+            discover().components,
+            expected
+                .iter()
+                .map(|(x, y)| (x.to_string(), y.to_string()))
+                .collect::<Vec<(String, String)>>()
+        );
+    }
+
+    #[test]
+    fn name_rs_is_preferred_over_mod_rs_and_missing_modules_are_skipped() {
+        let fixture = Fixture::new("game");
+        fixture
+            .file("src/lib.rs", "mod physics; mod missing;")
+            .file(
+                "src/physics.rs",
+                "#[derive(Component)] pub struct Position;",
+            )
+            .file(
+                "src/physics/mod.rs",
+                "#[derive(Component)] pub struct Velocity;",
+            );
+
+        let discovery = discover();
+
+        assert_eq!(
+            discovery.components,
+            vec![(
+                "Position".to_string(),
+                "'game::physics::Position'".to_string()
+            )]
+        );
+        assert_eq!(
+            discovery.sources,
+            vec![
+                fixture.dir.join("src/lib.rs"),
+                fixture.dir.join("src/physics.rs")
+            ]
+        );
+    }
+
+    #[test]
+    fn engines_under_opposite_cfgs_are_not_duplicates() {
+        let fixture = Fixture::new("game");
+        fixture.file(
+            "src/lib.rs",
+            r#"
+            #[cfg(test)] engine!();
+            #[cfg(not(test))] mod app { engine!(); }
+            #[cfg(feature = "editor")] mod editor { engine!(); }
+            #[cfg(not(feature = "editor"))] mod player { engine!(); }
+            "#,
+        );
+
+        // The editor and the player each clash with the test engine and the app
+        assert_eq!(
+            find_engine_invocations(),
+            vec![
+                "game #[cfg(test)]",
+                "game::app #[cfg(not(test))]",
+                "game::editor #[cfg(feature = \"editor\")]",
+                "game::player #[cfg(not(feature = \"editor\"))]",
+            ]
+        );
+
+        fixture.file(
+            "src/lib.rs",
+            r#"
+            #[cfg(test)] engine!();
+            #[cfg(not(test))] mod app { engine!(); }
+            "#,
+        );
+        assert!(find_engine_invocations().is_empty());
+    }
+
+    #[test]
+    fn the_crate_root_depends_on_the_target_being_compiled() {
+        let fixture = Fixture::new("game");
+        fixture
+            .file("src/lib.rs", "")
+            .file("src/main.rs", "")
+            .file("src/bin/tool/main.rs", "")
+            .file("tests/physics/main.rs", "");
+
+        assert_eq!(crate_root(), Some(fixture.dir.join("src/lib.rs")));
+
+        env::set_var("CARGO_CRATE_NAME", "tool");
+        env::set_var("CARGO_BIN_NAME", "tool");
+        assert_eq!(crate_root(), Some(fixture.dir.join("src/bin/tool/main.rs")));
+
+        env::set_var("CARGO_CRATE_NAME", "game");
+        env::set_var("CARGO_BIN_NAME", "game");
+        assert_eq!(crate_root(), Some(fixture.dir.join("src/main.rs")));
+
+        env::remove_var("CARGO_BIN_NAME");
+        env::set_var("CARGO_CRATE_NAME", "physics");
+        assert_eq!(
+            crate_root(),
+            Some(fixture.dir.join("tests/physics/main.rs"))
+        );
+
+        // A library renamed in Cargo.toml isn't named after the package either
+        env::set_var("CARGO_CRATE_NAME", "game_core");
+        assert_eq!(crate_root(), Some(fixture.dir.join("src/lib.rs")));
+    }
 }
@@ -11,6 +11,9 @@ use crate::manifestation::lift_off::{
     configure_surface, create_instance, create_screen, get_adapter, get_device,
 };
 
+// Renderer of engines which don't open a window
+pub struct Headless;
+
 pub struct Screen {
     pub event_loop: Option<EventLoop<()>>,
     pub window: Window,
@@ -22,6 +25,8 @@ pub struct Screen {
     pub queue: Queue,
 }
 
+// No Default, building a screen opens a window
+#[allow(clippy::new_without_default)]
 impl Screen {
     pub fn new() -> Self {
         let instance = create_instance();
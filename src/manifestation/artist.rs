@@ -1,26 +1,29 @@
 use winit::dpi::PhysicalSize;
-/*
-use crate::Engine;
+
+use crate::{cockpit::IgnitionEngine, manifestation::Screen};
 
 pub mod commands;
 pub mod pipeline;
 
-impl Engine {
-    pub fn resize(&mut self, new_size: PhysicalSize<u32>) {
+pub trait Artist: IgnitionEngine<Renderer = Screen> {
+    fn resize(&mut self, new_size: PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
-            self.config.size = new_size;
+            self.config_mut().size = new_size;
 
-            self.renderer.config.width = new_size.width;
-            self.renderer.config.height = new_size.height;
+            let renderer = self.renderer_mut();
+            renderer.config.width = new_size.width;
+            renderer.config.height = new_size.height;
 
             self.configure_surface();
         }
     }
 
-    pub fn configure_surface(&mut self) {
-        self.renderer
+    fn configure_surface(&mut self) {
+        let renderer = self.renderer();
+        renderer
             .surface
-            .configure(&self.renderer.device, &self.renderer.config);
+            .configure(&renderer.device, &renderer.config);
     }
 }
-*/
+
+impl<E: IgnitionEngine<Renderer = Screen> + ?Sized> Artist for E {}
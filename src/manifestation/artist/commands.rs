@@ -5,8 +5,8 @@ use wgpu::{
     TextureViewDescriptor,
 };
 use winit::event_loop::ControlFlow;
-/*
-use crate::Engine;
+
+use crate::{cockpit::IgnitionEngine, manifestation::artist::Artist, manifestation::Screen};
 
 pub struct ScreenEncoder {
     frame: SurfaceTexture,
@@ -15,8 +15,10 @@ pub struct ScreenEncoder {
     encoder: CommandEncoder,
 }
 
-impl Engine {
-    pub fn encoder(&mut self) -> Result<ScreenEncoder, ()> {
+// Surface errors are logged and handled where they happen, callers only have to skip the frame
+pub trait Commands: IgnitionEngine<Renderer = Screen> {
+    #[allow(clippy::result_unit_err)]
+    fn encoder(&mut self) -> Result<ScreenEncoder, ()> {
         let frame = create_frame(self)?;
         let view = create_view(&frame);
 
@@ -30,7 +32,7 @@ impl Engine {
         })
     }
 
-    pub fn render_pass<'a>(&'a mut self, encoder: &'a mut ScreenEncoder) -> RenderPass {
+    fn render_pass<'a>(&'a mut self, encoder: &'a mut ScreenEncoder) -> RenderPass<'a> {
         encoder.encoder.begin_render_pass(&RenderPassDescriptor {
             label: None,
             color_attachments: &[Some(RenderPassColorAttachment {
@@ -50,9 +52,10 @@ impl Engine {
         })
     }
 
-    pub fn render(&mut self, encoder: ScreenEncoder) -> Result<(), ()> {
+    #[allow(clippy::result_unit_err)]
+    fn render(&mut self, encoder: ScreenEncoder) -> Result<(), ()> {
         let command_buffer = Some(encoder.encoder.finish());
-        self.renderer.queue.submit(command_buffer);
+        self.renderer().queue.submit(command_buffer);
 
         encoder.frame.present();
 
@@ -60,16 +63,22 @@ impl Engine {
     }
 }
 
-pub fn create_frame(engine: &mut Engine) -> Result<SurfaceTexture, ()> {
-    match engine.renderer.surface.get_current_texture() {
+impl<E: IgnitionEngine<Renderer = Screen> + ?Sized> Commands for E {}
+
+#[allow(clippy::result_unit_err)]
+pub fn create_frame<E: IgnitionEngine<Renderer = Screen> + ?Sized>(
+    engine: &mut E,
+) -> Result<SurfaceTexture, ()> {
+    match engine.renderer().surface.get_current_texture() {
         Ok(frame) => Ok(frame),
         Err(SurfaceError::Lost) => {
-            engine.resize(engine.config.size);
+            let size = engine.config().size;
+            engine.resize(size);
             Err(())
         }
         Err(SurfaceError::OutOfMemory) => {
             error!("GPU out of memory - Ignition");
-            engine.config.control_flow = ControlFlow::Exit;
+            engine.config_mut().control_flow = ControlFlow::Exit;
             Err(())
         }
         Err(e) => {
@@ -83,8 +92,9 @@ pub fn create_view(frame: &SurfaceTexture) -> TextureView {
     frame.texture.create_view(&TextureViewDescriptor::default())
 }
 
-pub fn create_command_encoder(engine: &mut Engine) -> CommandEncoder {
+pub fn create_command_encoder<E: IgnitionEngine<Renderer = Screen> + ?Sized>(
+    engine: &mut E,
+) -> CommandEncoder {
     let descriptor = &CommandEncoderDescriptor { label: None };
-    engine.renderer.device.create_command_encoder(descriptor)
+    engine.renderer().device.create_command_encoder(descriptor)
 }
-*/
@@ -48,7 +48,7 @@ pub fn configure_surface(
     device: &Device,
 ) -> SurfaceConfiguration {
     let config = generate_default_configuration(surface, adapter);
-    surface.configure(&device, &config);
+    surface.configure(device, &config);
 
     config
 }
@@ -59,7 +59,7 @@ pub fn generate_default_configuration(
 ) -> SurfaceConfiguration {
     SurfaceConfiguration {
         usage: TextureUsages::RENDER_ATTACHMENT,
-        format: surface.get_supported_formats(&adapter)[0],
+        format: surface.get_supported_formats(adapter)[0],
         width: 1920,
         height: 1080,
         present_mode: PresentMode::Fifo,
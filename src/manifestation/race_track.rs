@@ -3,49 +3,55 @@ use winit::{
     event_loop::{ControlFlow, EventLoop},
     platform::run_return::EventLoopExtRunReturn,
 };
-/*
-use crate::Engine;
 
-impl Engine {
-    pub fn event_loop(self, closure: impl FnMut(&mut Engine) -> Result<(), ()> + 'static) {
-        match self.config.any_thread {
+use crate::{cockpit::IgnitionEngine, manifestation::artist::Artist, manifestation::Screen};
+
+pub trait RaceTrack: IgnitionEngine<Renderer = Screen> + Sized {
+    fn event_loop(self, closure: impl FnMut(&mut Self) -> Result<(), ()> + 'static)
+    where
+        Self: 'static,
+    {
+        match self.config().any_thread {
             true => self.run_return(closure),
             false => self.run(closure),
         }
     }
 
-    pub fn run(mut self, mut closure: impl FnMut(&mut Engine) -> Result<(), ()> + 'static) {
+    fn run(mut self, mut closure: impl FnMut(&mut Self) -> Result<(), ()> + 'static)
+    where
+        Self: 'static,
+    {
         self.take_event_loop().run(move |event, _, control_flow| {
             self.event(event, control_flow, &mut closure);
         });
     }
 
-    pub fn run_return(mut self, mut closure: impl FnMut(&mut Engine) -> Result<(), ()>) {
+    fn run_return(mut self, mut closure: impl FnMut(&mut Self) -> Result<(), ()>) {
         self.take_event_loop().run_return(|event, _, control_flow| {
             self.event(event, control_flow, &mut closure);
         });
     }
 
-    pub fn run_once(&mut self, mut closure: impl FnMut(&mut Engine) -> Result<(), ()>) {
+    fn run_once(&mut self, mut closure: impl FnMut(&mut Self) -> Result<(), ()>) {
         let mut event_loop = self.take_event_loop();
 
         event_loop.run_return(|event, _, control_flow| {
-            self.event(event, control_flow, &mut |engine: &mut Engine| {
-                engine.config.control_flow = ControlFlow::Exit;
+            self.event(event, control_flow, &mut |engine: &mut Self| {
+                engine.config_mut().control_flow = ControlFlow::Exit;
                 closure(engine)
             });
         });
 
-        self.renderer.event_loop = Some(event_loop);
+        self.renderer_mut().event_loop = Some(event_loop);
     }
 
-    pub fn event<T>(
+    fn event<T>(
         &mut self,
         event: Event<T>,
         control_flow: &mut ControlFlow,
-        closure: &mut impl FnMut(&mut Engine) -> Result<(), ()>,
+        closure: &mut impl FnMut(&mut Self) -> Result<(), ()>,
     ) {
-        *control_flow = self.config.control_flow;
+        *control_flow = self.config().control_flow;
 
         match event {
             Event::WindowEvent {
@@ -63,17 +69,16 @@ impl Engine {
 
             Event::RedrawRequested(_) => {}
 
-            Event::MainEventsCleared => {
-                if closure(self).is_ok() {
-                    self.renderer.window.request_redraw();
-                }
+            Event::MainEventsCleared if closure(self).is_ok() => {
+                self.renderer().window.request_redraw();
             }
             _ => {}
         }
     }
 
-    pub fn take_event_loop(&mut self) -> EventLoop<()> {
-        self.renderer.event_loop.take().unwrap()
+    fn take_event_loop(&mut self) -> EventLoop<()> {
+        self.renderer_mut().event_loop.take().unwrap()
     }
 }
-*/
+
+impl<E: IgnitionEngine<Renderer = Screen>> RaceTrack for E {}
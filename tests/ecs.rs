@@ -0,0 +1,308 @@
+extern crate ignition;
+
+use ignition::{
+    life::{colony::Archetypes, glitch::LifeError, Scene},
+    prelude::*,
+};
+
+#[derive(Component, Debug, PartialEq)]
+pub struct Position(f32, f32);
+
+#[derive(Component, Debug, PartialEq)]
+pub struct Velocity(f32, f32);
+
+#[derive(Component, Debug, PartialEq)]
+pub enum State {
+    Idle,
+    Walking,
+}
+
+#[derive(Component, Debug, PartialEq)]
+pub struct Health<T>(T);
+
+pub mod ui {
+    use ignition::prelude::*;
+
+    #[derive(Component, Debug, PartialEq)]
+    pub struct Position(pub u32, pub u32);
+}
+
+#[cfg(test)]
+#[derive(Component, Debug, PartialEq)]
+pub struct Marker;
+
+#[cfg(not(test))]
+#[derive(Component, Debug, PartialEq)]
+pub struct Gizmo;
+
+ecs!(components: [
+    Position,
+    Velocity,
+    State,
+    Health<f32>,
+    Health<u32>,
+    ui::Position,
+    #[cfg(test)] Marker,
+    #[cfg(not(test))] Gizmo,
+]);
+
+#[test]
+fn ecs_runs_gameplay_logic_without_a_window() {
+    let mut scene: Scene<ComponentPools> = Scene::new();
+
+    let entity = scene.entity();
+    scene.component(entity, Position(0.0, 0.0));
+    scene.component(entity, Velocity(1.0, 2.0));
+
+    let velocity = scene.get_component::<Velocity>(entity).unwrap();
+    let (dx, dy) = (velocity.0, velocity.1);
+
+    let position = scene.get_component_mut::<Position>(entity).unwrap();
+    position.0 += dx;
+    position.1 += dy;
+
+    assert_eq!(
+        scene.get_component::<Position>(entity),
+        Ok(&Position(1.0, 2.0))
+    );
+
+    scene.delete(entity);
+
+    assert!(scene.get::<Position>().iter().next().is_none());
+    assert!(scene.get::<Velocity>().iter().next().is_none());
+}
+
+#[test]
+fn enum_and_generic_components_get_their_own_pools() {
+    let mut scene: Scene<ComponentPools> = Scene::new();
+
+    let entity = scene.entity();
+    scene.component(entity, State::Walking);
+    scene.component(entity, Health(0.5_f32));
+    scene.component(entity, Health(3_u32));
+
+    assert_eq!(scene.get_component::<State>(entity), Ok(&State::Walking));
+    assert_eq!(scene.get_component::<Health<f32>>(entity), Ok(&Health(0.5)));
+    assert_eq!(scene.get_component::<Health<u32>>(entity), Ok(&Health(3)));
+
+    scene.take_component::<Health<f32>>(entity).unwrap();
+
+    assert!(scene.get_component::<Health<f32>>(entity).is_err());
+    assert_eq!(scene.component_pools.health_u32.iter().count(), 1);
+}
+
+#[test]
+fn components_sharing_a_name_get_their_own_pools() {
+    let mut scene: Scene<ComponentPools> = Scene::new();
+
+    let entity = scene.entity();
+    scene.component(entity, Position(0.5, 0.5));
+    scene.component(entity, ui::Position(640, 480));
+
+    assert_eq!(
+        scene.get_component::<Position>(entity),
+        Ok(&Position(0.5, 0.5))
+    );
+    assert_eq!(
+        scene.get::<ui::Position>().get(entity),
+        Ok(&ui::Position(640, 480))
+    );
+
+    assert_eq!(scene.component_pools.position.iter().count(), 1);
+    assert_eq!(scene.component_pools.ui_position.iter().count(), 1);
+}
+
+#[test]
+fn components_compiled_out_get_no_pool() {
+    let mut scene: Scene<ComponentPools> = Scene::new();
+
+    let entity = scene.entity();
+    scene.component(entity, Marker);
+
+    assert_eq!(scene.get_component::<Marker>(entity), Ok(&Marker));
+
+    let names = scene.dump().entities[0]
+        .components
+        .iter()
+        .map(|x| x.name)
+        .collect::<Vec<&str>>();
+    assert_eq!(names, vec!["Marker"]);
+
+    scene.delete(entity);
+    assert!(scene.get::<Marker>().iter().next().is_none());
+}
+
+#[test]
+fn pools_of_distinct_components_can_be_borrowed_together() {
+    let mut scene: Scene<ComponentPools> = Scene::new();
+
+    let entity = scene.entity();
+    scene.component(entity, Position(0.0, 0.0));
+    scene.component(entity, Velocity(1.0, 2.0));
+
+    let (positions, velocities) = scene.get_many_mut::<(Position, Velocity)>().unwrap();
+    let velocity = velocities.get(entity).unwrap();
+    let position = positions.get_mut(entity).unwrap();
+    position.0 += velocity.0;
+    position.1 += velocity.1;
+
+    let ComponentPoolsMut {
+        position, state, ..
+    } = scene.component_pools.split();
+    position.get_mut(entity).unwrap().0 += 1.0;
+    assert!(state.get(entity).is_err());
+
+    assert_eq!(
+        scene.get_component::<Position>(entity),
+        Ok(&Position(2.0, 2.0))
+    );
+    assert_eq!(
+        scene.get_many_mut::<(Velocity, Position, Velocity)>().err(),
+        Some(LifeError::PoolBorrowedTwice("ecs::Velocity"))
+    );
+}
+
+#[derive(Bundle, Debug, PartialEq)]
+pub struct Mover {
+    position: Position,
+    velocity: Velocity,
+    health: Health<u32>,
+}
+
+#[test]
+fn bundles_insert_and_take_every_component() {
+    let mut scene: Scene<ComponentPools> = Scene::new();
+
+    let mover = Mover {
+        position: Position(0.0, 0.0),
+        velocity: Velocity(1.0, 2.0),
+        health: Health(3),
+    };
+    let entity = scene.spawn_bundle(mover);
+
+    assert_eq!(
+        scene.get_component::<Velocity>(entity),
+        Ok(&Velocity(1.0, 2.0))
+    );
+    assert_eq!(scene.get_component::<Health<u32>>(entity), Ok(&Health(3)));
+
+    let mover = scene.take_bundle::<Mover>(entity).unwrap();
+
+    assert_eq!(mover.position, Position(0.0, 0.0));
+    assert!(scene.dump().entities[0].components.is_empty());
+}
+
+#[test]
+fn taking_an_incomplete_bundle_leaves_the_entity_untouched() {
+    let mut scene: Scene<ComponentPools> = Scene::new();
+
+    let entity = scene.entity();
+    scene.component(entity, Velocity(1.0, 2.0));
+
+    assert_eq!(
+        scene.take_bundle::<Mover>(entity),
+        Err(LifeError::IncompleteBundle(
+            "ecs::Mover",
+            entity,
+            vec!["Position", "Health<u32>"]
+        ))
+    );
+    assert_eq!(
+        scene.get_component::<Velocity>(entity),
+        Ok(&Velocity(1.0, 2.0))
+    );
+}
+
+#[test]
+fn disabling_an_entity_disables_all_of_its_components_until_enabled_back() {
+    let mut scene: Scene<ComponentPools> = Scene::new();
+
+    let entity = scene.entity();
+    scene.component(entity, Position(0.0, 0.0));
+    scene.component(entity, Velocity(1.0, 2.0));
+    scene.component(entity, State::Idle);
+    scene.disable::<State>(entity);
+
+    let other = scene.entity();
+    scene.component(other, Position(5.0, 5.0));
+
+    scene.try_disable_entity(entity).unwrap();
+
+    let dump = scene.dump();
+    assert!(dump.entities[0].components.iter().all(|x| !x.enabled));
+    assert!(dump.entities[1].components.iter().all(|x| x.enabled));
+    assert_eq!(scene.get::<Position>().iter().count(), 1);
+
+    scene.try_enable_entity(entity).unwrap();
+
+    // `State` was disabled on its own beforehand, so it stays disabled
+    let dump = scene.dump();
+    assert!(dump.entities[0]
+        .components
+        .iter()
+        .all(|x| x.enabled == (x.name != "State")));
+    assert_eq!(scene.get::<Position>().iter().count(), 2);
+    assert_eq!(
+        scene.try_enable::<State>(entity),
+        Ok(()),
+        "State should still be disabled"
+    );
+    assert!(scene.validate().is_valid());
+
+    scene.delete(entity);
+    assert_eq!(
+        scene.try_disable_entity(entity),
+        Err(LifeError::EntityNotAlive(entity))
+    );
+}
+
+#[test]
+fn both_storage_backends_answer_the_same_queries() {
+    let mut pools: Scene<ComponentPools> = Scene::new();
+    let mut archetypes: Scene<Archetypes> = Scene::new();
+
+    for i in 0..4 {
+        let entity = pools.entity();
+        pools.component(entity, Position(i as f32, 0.0));
+
+        let entity = archetypes.entity();
+        archetypes.component(entity, Position(i as f32, 0.0));
+
+        if i % 2 == 0 {
+            pools.component(entity, Velocity(1.0, 1.0));
+            archetypes.component(entity, Velocity(1.0, 1.0));
+        }
+    }
+
+    pools
+        .query_mut::<(Position, Velocity), _>(|_entity, (position, velocity)| {
+            position.0 += velocity.0;
+            position.1 += velocity.1;
+        })
+        .unwrap();
+    archetypes
+        .query_mut::<(Position, Velocity), _>(|_entity, (position, velocity)| {
+            position.0 += velocity.0;
+            position.1 += velocity.1;
+        })
+        .unwrap();
+
+    pools.delete(2);
+    archetypes.delete(2);
+    assert_eq!(
+        archetypes.take_component::<Velocity>(0),
+        Ok(Velocity(1.0, 1.0))
+    );
+    assert_eq!(pools.take_component::<Velocity>(0), Ok(Velocity(1.0, 1.0)));
+
+    for entity in 0..4 {
+        assert_eq!(
+            pools.get_component::<Position>(entity),
+            archetypes.get_component::<Position>(entity)
+        );
+    }
+    assert_eq!(
+        archetypes.get_component::<Position>(0),
+        Ok(&Position(1.0, 1.0))
+    );
+}
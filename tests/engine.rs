@@ -0,0 +1,33 @@
+extern crate ignition;
+
+use ignition::prelude::*;
+
+#[derive(Component, Debug, PartialEq)]
+pub struct Position(f32, f32);
+
+// Default name, right next to the glob import of the prelude
+engine!(renderer = Headless, components: [Position]);
+
+fn spawn<E: IgnitionEngine>(engine: &mut E) -> usize
+where
+    Position: ignition::life::Component<E::Pools>,
+{
+    let scene = engine.scene_mut();
+
+    let entity = scene.entity();
+    scene.component(entity, Position(0.0, 0.0));
+
+    entity
+}
+
+#[test]
+fn generic_helpers_work_with_the_default_engine() {
+    let mut engine = Engine::ignite();
+
+    let entity = spawn(&mut engine);
+
+    assert_eq!(
+        engine.scene.get_component::<Position>(entity),
+        Ok(&Position(0.0, 0.0))
+    );
+}
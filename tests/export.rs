@@ -0,0 +1,32 @@
+extern crate ignition;
+
+use exporter::{Gizmo, Velocity};
+use ignition::{life::Scene, prelude::*};
+
+#[derive(Component, Debug, PartialEq)]
+pub struct Position(f32, f32);
+
+ecs!(components: [Position], crates: [exporter]);
+
+#[test]
+fn imported_components_keep_the_cfgs_of_their_crate() {
+    let mut scene: Scene<ComponentPools> = Scene::new();
+
+    let entity = scene.entity();
+    scene.component(entity, Position(0.0, 0.0));
+    scene.component(entity, Velocity(1.0, 2.0));
+    scene.component(entity, Gizmo);
+
+    assert_eq!(
+        scene.get_component::<Velocity>(entity),
+        Ok(&Velocity(1.0, 2.0))
+    );
+
+    // `exporter::Marker` is compiled out of its crate, even though this one is built for tests
+    let names = scene.dump().entities[0]
+        .components
+        .iter()
+        .map(|x| x.name)
+        .collect::<Vec<&str>>();
+    assert_eq!(names, vec!["Position", "Velocity", "Gizmo"]);
+}
@@ -0,0 +1,20 @@
+// Exports components to tests/export.rs the way a dependency crate would
+use ignition::prelude::*;
+
+#[derive(Component, Debug, PartialEq)]
+pub struct Velocity(pub f32, pub f32);
+
+// Never compiled in, as a dependency isn't built for tests
+#[cfg(test)]
+#[derive(Component, Debug, PartialEq)]
+pub struct Marker;
+
+#[cfg(not(test))]
+#[derive(Component, Debug, PartialEq)]
+pub struct Gizmo;
+
+export_components!(components: [
+    Velocity,
+    #[cfg(test)] Marker,
+    #[cfg(not(test))] Gizmo,
+]);
@@ -0,0 +1,35 @@
+extern crate ignition;
+
+use ignition::prelude::*;
+
+#[derive(Component, Debug, PartialEq)]
+pub struct Position(f32, f32);
+
+// Only listed by hand below, so it stays out of components.toml
+#[derive(Component, Debug, PartialEq)]
+#[component(no_registry)]
+pub struct Velocity(f32, f32);
+
+engine!(name = Sim, renderer = Headless, components: [Position, Velocity]);
+
+#[test]
+fn headless_engines_run_without_a_window() {
+    let mut sim = Sim::ignite();
+
+    let entity = sim.scene.entity();
+    sim.scene.component(entity, Position(0.0, 0.0));
+    sim.scene.component(entity, Velocity(1.0, 2.0));
+
+    // One tick
+    let velocity = sim.scene.get_component::<Velocity>(entity).unwrap();
+    let (dx, dy) = (velocity.0, velocity.1);
+
+    let position = sim.scene.get_component_mut::<Position>(entity).unwrap();
+    position.0 += dx;
+    position.1 += dy;
+
+    assert_eq!(
+        sim.scene.get_component::<Position>(entity),
+        Ok(&Position(1.0, 2.0))
+    );
+}
@@ -0,0 +1,104 @@
+extern crate ignition;
+
+use std::collections::HashMap;
+
+use ignition::life::{annihilation::EntityDestructor, ghost::ComponentToggler, ComponentPool};
+
+const ENTITIES: u64 = 24;
+const OPERATIONS: usize = 20_000;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, max: u64) -> u64 {
+        self.next() % max
+    }
+}
+
+// The model maps each entity to its component and whether it is enabled
+fn compare(pool: &ComponentPool<i64>, model: &HashMap<usize, (i64, bool)>, step: usize) {
+    let report = pool.validate();
+    assert!(report.is_valid(), "step {} : {}", step, report);
+
+    for entity in 0..ENTITIES as usize {
+        assert_eq!(
+            pool.get(entity).ok(),
+            model.get(&entity).map(|(value, _)| value),
+            "step {} : entity {}",
+            step,
+            entity
+        );
+    }
+
+    let mut enabled = pool.iter().cloned().collect::<Vec<i64>>();
+    let mut expected = model
+        .values()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(value, _)| *value)
+        .collect::<Vec<i64>>();
+
+    enabled.sort();
+    expected.sort();
+
+    assert_eq!(enabled, expected, "step {}", step);
+}
+
+#[test]
+fn random_operations_match_hashmap_model() {
+    let mut rng = XorShift(0x2545F4914F6CDD1D);
+
+    let mut pool: ComponentPool<i64> = ComponentPool::empty();
+    let mut model: HashMap<usize, (i64, bool)> = HashMap::new();
+
+    for step in 0..OPERATIONS {
+        let entity = rng.below(ENTITIES) as usize;
+
+        match rng.below(5) {
+            0 | 1 => {
+                let value = rng.next() as i64;
+                pool.try_assign_component(entity, value).unwrap();
+
+                let enabled = model.get(&entity).is_none_or(|(_, enabled)| *enabled);
+                model.insert(entity, (value, enabled));
+            }
+            2 => match model.remove(&entity) {
+                Some((value, _)) => assert_eq!(pool.take_entity(entity), Ok(value)),
+                None => {
+                    assert!(pool.try_delete_entity(entity).is_ok());
+                    assert!(pool.take_entity(entity).is_err());
+                }
+            },
+            3 => {
+                let result = pool.try_disable_entity(entity);
+
+                match model.get_mut(&entity) {
+                    Some((_, enabled)) if *enabled => {
+                        assert!(result.is_ok(), "step {} : {:?}", step, result);
+                        *enabled = false;
+                    }
+                    _ => assert!(result.is_err()),
+                }
+            }
+            _ => {
+                let result = pool.try_enable_entity(entity);
+
+                match model.get_mut(&entity) {
+                    Some((_, enabled)) if !*enabled => {
+                        assert!(result.is_ok(), "step {} : {:?}", step, result);
+                        *enabled = true;
+                    }
+                    _ => assert!(result.is_err()),
+                }
+            }
+        }
+
+        compare(&pool, &model, step);
+    }
+}